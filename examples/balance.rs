@@ -0,0 +1,377 @@
+//! A batch report of bot campaigns for balancing levels and upgrades.
+//!
+//! Usage: `cargo run --example balance --features balance -- --runs 1000 --seed 0 --out balance`.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::path::PathBuf;
+
+use flux_pursuit::deck::DeckConfig;
+use flux_pursuit::deck::policy::PolicyKind;
+use flux_pursuit::level::LevelConfig;
+use flux_pursuit::menu::shop::ShopConfig;
+use flux_pursuit::menu::upgrade::UpgradeConfig;
+use flux_pursuit::module::ModuleConfig;
+use flux_pursuit::prelude::*;
+use flux_pursuit::projectile::ProjectileConfig;
+use flux_pursuit::sim::CampaignReport;
+use flux_pursuit::sim::Outcome;
+use flux_pursuit::sim::Simulator;
+
+fn main() -> AppExit {
+    match try_run() {
+        Ok(()) => AppExit::Success,
+        Err(error) => {
+            eprintln!("error: {error}");
+            AppExit::error()
+        },
+    }
+}
+
+fn try_run() -> Result<(), String> {
+    let args = Args::parse()?;
+    let deck_config = load_config::<DeckConfig>()?;
+    let level_config = load_config::<LevelConfig>()?;
+    let module_config = load_config::<ModuleConfig>()?;
+    let projectile_config = load_config::<ProjectileConfig>()?;
+    let upgrade_config = load_config::<UpgradeConfig>()?;
+    let shop_config = load_config::<ShopConfig>()?;
+    if deck_config.player_decks.is_empty() {
+        return Err("deck.ron has no player decks".to_string());
+    }
+
+    let simulator = Simulator {
+        level_config: &level_config,
+        module_config: &module_config,
+        projectile_config: &projectile_config,
+        upgrade_config: &upgrade_config,
+        shop_config: &shop_config,
+        max_rounds: args.max_rounds,
+        max_helm_moves: 100,
+    };
+
+    let mut reports = vec![];
+    for player_deck in &deck_config.player_decks {
+        for policy_kind in PolicyKind::ALL {
+            let mut report = PolicyReport::new(&player_deck.name, policy_kind, &level_config);
+            for i in 0..args.runs {
+                let seed = args.seed.wrapping_add(i as u64);
+                let mut policy = policy_kind.build(seed);
+                report.add(&simulator.simulate_campaign(player_deck.clone(), &mut *policy, seed));
+            }
+            println!(
+                "{} / {}: won {} / {} campaigns",
+                report.ship, report.name, report.wins, report.campaigns,
+            );
+            reports.push(report);
+        }
+    }
+
+    std::fs::create_dir_all(&args.out).map_err(|e| e.to_string())?;
+    for (file, contents) in [
+        ("summary.csv", summary_csv(&reports)),
+        ("levels.csv", levels_csv(&reports, &level_config)),
+        ("upgrades.csv", upgrades_csv(&reports)),
+        ("report.md", markdown(&reports, &level_config, &args)),
+    ] {
+        let path = args.out.join(file);
+        std::fs::write(&path, contents).map_err(|e| format!("{}: {e}", path.display()))?;
+    }
+    println!("Wrote report to {}", args.out.display());
+
+    Ok(())
+}
+
+struct Args {
+    runs: usize,
+    seed: u64,
+    max_rounds: usize,
+    out: PathBuf,
+}
+
+impl Args {
+    fn parse() -> Result<Self, String> {
+        let mut args = Self {
+            runs: 1000,
+            seed: 0,
+            max_rounds: 100,
+            out: PathBuf::from("balance"),
+        };
+
+        let mut iter = std::env::args().skip(1);
+        while let Some(flag) = iter.next() {
+            let value = iter.next().ok_or(format!("missing value for {flag}"))?;
+            let invalid = |_| format!("invalid value for {flag}: {value}");
+            match flag.as_str() {
+                "--runs" => args.runs = value.parse().map_err(invalid)?,
+                "--seed" => args.seed = value.parse().map_err(invalid)?,
+                "--max-rounds" => args.max_rounds = value.parse().map_err(invalid)?,
+                "--out" => args.out = PathBuf::from(&value),
+                _ => return Err(format!("unknown flag: {flag}")),
+            }
+        }
+
+        Ok(args)
+    }
+}
+
+/// Read a config file directly from the assets folder.
+fn load_config<C: Config>() -> Result<C, String> {
+    let root = std::env::var("CARGO_MANIFEST_DIR").unwrap_or_else(|_| ".".to_string());
+    let path = PathBuf::from(root)
+        .join("assets")
+        .join(C::FOLDER)
+        .join(C::FILE);
+    let text = std::fs::read_to_string(&path).map_err(|e| format!("{}: {e}", path.display()))?;
+
+    ron::from_str(&text).map_err(|e| format!("{}: {e}", path.display()))
+}
+
+struct PolicyReport {
+    ship: String,
+    name: &'static str,
+    campaigns: usize,
+    wins: usize,
+    levels_cleared: usize,
+    levels: Vec<LevelStats>,
+    upgrades: BTreeMap<String, UpgradeStats>,
+}
+
+#[derive(Default)]
+struct LevelStats {
+    reached: usize,
+    victories: usize,
+    defeats: usize,
+    timeouts: usize,
+    /// Attempts that stopped because the simulator can't model the level.
+    unsupported: usize,
+    rounds: usize,
+}
+
+#[derive(Default)]
+struct UpgradeStats {
+    /// Total times picked.
+    picks: usize,
+    /// Campaigns that picked it at least once.
+    campaigns: usize,
+    /// Campaigns that picked it at least once and won.
+    wins: usize,
+}
+
+impl PolicyReport {
+    fn new(ship: &str, policy_kind: PolicyKind, level_config: &LevelConfig) -> Self {
+        Self {
+            ship: ship.to_string(),
+            name: policy_kind.name(),
+            campaigns: 0,
+            wins: 0,
+            levels_cleared: 0,
+            levels: level_config.levels.iter().map(|_| default()).collect(),
+            upgrades: BTreeMap::new(),
+        }
+    }
+
+    fn add(&mut self, campaign: &CampaignReport) {
+        let won = campaign.outcome == Outcome::Victory;
+        self.campaigns += 1;
+        self.wins += won as usize;
+
+        for (stats, level) in self.levels.iter_mut().zip(&campaign.levels) {
+            stats.reached += 1;
+            stats.rounds += level.rounds.len();
+            match level.outcome {
+                Outcome::Victory => {
+                    stats.victories += 1;
+                    self.levels_cleared += 1;
+                },
+                Outcome::Defeat => stats.defeats += 1,
+                Outcome::Timeout => stats.timeouts += 1,
+                Outcome::Unsupported => stats.unsupported += 1,
+            }
+        }
+
+        let mut picked = HashSet::<&str>::default();
+        for name in campaign.picks.iter().flatten() {
+            self.upgrades.entry(name.clone()).or_default().picks += 1;
+            picked.insert(name);
+        }
+        for name in picked {
+            let stats = self.upgrades.entry(name.to_string()).or_default();
+            stats.campaigns += 1;
+            stats.wins += won as usize;
+        }
+    }
+
+    fn win_rate(&self) -> f32 {
+        ratio(self.wins, self.campaigns)
+    }
+
+    /// Win rate of campaigns that did and did not pick the upgrade.
+    fn win_rate_with(&self, stats: &UpgradeStats) -> (f32, f32) {
+        (
+            ratio(stats.wins, stats.campaigns),
+            ratio(self.wins - stats.wins, self.campaigns - stats.campaigns),
+        )
+    }
+}
+
+fn ratio(a: usize, b: usize) -> f32 {
+    if b == 0 { 0.0 } else { a as f32 / b as f32 }
+}
+
+fn summary_csv(reports: &[PolicyReport]) -> String {
+    let mut csv = "ship,policy,campaigns,wins,win_rate,avg_levels_cleared\n".to_string();
+    for report in reports {
+        let _ = writeln!(
+            csv,
+            "{:?},{},{},{},{:.4},{:.2}",
+            report.ship,
+            report.name,
+            report.campaigns,
+            report.wins,
+            report.win_rate(),
+            ratio(report.levels_cleared, report.campaigns),
+        );
+    }
+
+    csv
+}
+
+fn levels_csv(reports: &[PolicyReport], level_config: &LevelConfig) -> String {
+    let mut csv = "ship,policy,level,name,reached,victories,defeats,timeouts,unsupported,\
+        win_rate,avg_rounds\n"
+        .to_string();
+    for report in reports {
+        for (level, (stats, setup)) in report.levels.iter().zip(&level_config.levels).enumerate() {
+            let _ = writeln!(
+                csv,
+                "{:?},{},{},{:?},{},{},{},{},{},{:.4},{:.2}",
+                report.ship,
+                report.name,
+                level,
+                setup.name,
+                stats.reached,
+                stats.victories,
+                stats.defeats,
+                stats.timeouts,
+                stats.unsupported,
+                ratio(stats.victories, stats.reached),
+                ratio(stats.rounds, stats.reached),
+            );
+        }
+    }
+
+    csv
+}
+
+fn upgrades_csv(reports: &[PolicyReport]) -> String {
+    let mut csv =
+        "ship,policy,upgrade,picks,campaigns,win_rate_with,win_rate_without,lift\n".to_string();
+    for report in reports {
+        for (name, stats) in &report.upgrades {
+            let (with, without) = report.win_rate_with(stats);
+            let _ = writeln!(
+                csv,
+                "{:?},{},{},{},{},{:.4},{:.4},{:.4}",
+                report.ship,
+                report.name,
+                name,
+                stats.picks,
+                stats.campaigns,
+                with,
+                without,
+                with - without,
+            );
+        }
+    }
+
+    csv
+}
+
+fn markdown(reports: &[PolicyReport], level_config: &LevelConfig, args: &Args) -> String {
+    let mut md = format!(
+        "# Balance report\n\n\
+        {} seeded campaigns per ship and policy, starting from seed {}, \
+        with at most {} rounds per level.\n\n\
+        ## Win rates\n\n\
+        | Ship | Policy | Win rate | Avg levels cleared |\n\
+        | --- | --- | --- | --- |\n",
+        args.runs, args.seed, args.max_rounds,
+    );
+    for report in reports {
+        let _ = writeln!(
+            md,
+            "| {} | {} | {:.1}% | {:.2} |",
+            report.ship,
+            report.name,
+            100.0 * report.win_rate(),
+            ratio(report.levels_cleared, report.campaigns),
+        );
+    }
+
+    md.push_str(
+        "\n## Levels\n\n\
+        Win rate and average rounds of each attempt at the level. Campaigns stop at levels \
+        the simulator can't model, such as asteroid fields.\n\n",
+    );
+    md.push_str("| Level |");
+    for report in reports {
+        let _ = write!(md, " {} / {} |", report.ship, report.name);
+    }
+    md.push_str("\n| --- |");
+    md.push_str(&" --- |".repeat(reports.len()));
+    md.push('\n');
+    for (level, setup) in level_config.levels.iter().enumerate() {
+        let _ = write!(md, "| {} ({}) |", level, setup.name);
+        for report in reports {
+            let stats = &report.levels[level];
+            if stats.unsupported > 0 {
+                md.push_str(" not simulated |");
+                continue;
+            }
+            let _ = write!(
+                md,
+                " {:.1}% in {:.1} |",
+                100.0 * ratio(stats.victories, stats.reached),
+                ratio(stats.rounds, stats.reached),
+            );
+        }
+        md.push('\n');
+    }
+
+    md.push_str(
+        "\n## Upgrades\n\n\
+        Upgrades are picked at random, so the lift is the difference in win rate between \
+        campaigns that did and did not pick the upgrade. Longer campaigns pick more upgrades, \
+        so compare lifts against each other rather than against zero.\n",
+    );
+    for report in reports {
+        let _ = write!(
+            md,
+            "\n### {} / {}\n\n\
+            | Upgrade | Picks | Win rate with | Win rate without | Lift |\n\
+            | --- | --- | --- | --- | --- |\n",
+            report.ship, report.name,
+        );
+        let mut upgrades = report.upgrades.iter().collect::<Vec<_>>();
+        upgrades.sort_by(|(_, a), (_, b)| {
+            let a = report.win_rate_with(a);
+            let b = report.win_rate_with(b);
+            (b.0 - b.1).total_cmp(&(a.0 - a.1))
+        });
+        for (name, stats) in upgrades {
+            let (with, without) = report.win_rate_with(stats);
+            let _ = writeln!(
+                md,
+                "| {} | {} | {:.1}% | {:.1}% | {:+.1}% |",
+                name,
+                stats.picks,
+                100.0 * with,
+                100.0 * without,
+                100.0 * (with - without),
+            );
+        }
+    }
+
+    md
+}
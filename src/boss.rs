@@ -0,0 +1,100 @@
+use crate::deck::EnemyDeck;
+use crate::prelude::*;
+
+pub(super) fn plugin(app: &mut App) {
+    app.configure::<EnemyBehavior>();
+}
+
+/// How an enemy ship behaves beyond its deck.
+#[derive(Component, Reflect, Serialize, Deserialize, Clone, Default, Debug)]
+#[reflect(Component)]
+#[serde(deny_unknown_fields, default)]
+pub struct EnemyBehavior {
+    /// What the ship does once its health runs out.
+    pub defeat: Defeat,
+    /// Deck swaps as the ship loses health, entered in order.
+    pub phases: Vec<BossPhase>,
+    /// Extra flux the ship gains after holding out for a while.
+    pub enrage: Option<Enrage>,
+
+    /// The number of phases the ship has entered.
+    pub phase: usize,
+}
+
+impl Configure for EnemyBehavior {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+    }
+}
+
+impl EnemyBehavior {
+    /// Enter the next phase if its health threshold has been reached, swapping in its deck.
+    ///
+    /// Returns the phase that was entered.
+    pub fn update_phase(&mut self, deck: &mut EnemyDeck, health: f32) -> Option<&BossPhase> {
+        let phase = self.phases.get(self.phase)?;
+        rq!(deck.max_health > 0.0 && health <= phase.health * deck.max_health);
+        self.enter_next_phase(deck)
+    }
+
+    /// Enter the next phase regardless of health, swapping in its deck.
+    ///
+    /// The ship keeps its name, max health, shield and armor.
+    pub fn enter_next_phase(&mut self, deck: &mut EnemyDeck) -> Option<&BossPhase> {
+        let phase = self.phases.get(self.phase)?;
+        self.phase += 1;
+        *deck = EnemyDeck {
+            name: std::mem::take(&mut deck.name),
+            max_health: deck.max_health,
+            shield: deck.shield.take(),
+            armor: deck.armor.take(),
+            ..phase.deck.clone()
+        };
+        Some(phase)
+    }
+
+    /// The extra flux added to each of the ship's actions this round.
+    pub fn enrage_flux(&self, round: usize) -> f32 {
+        let enrage = rq!(self.enrage.as_ref());
+        rq!(round >= enrage.round);
+        enrage.flux
+    }
+
+    /// The enrage that begins this round, if any.
+    pub fn enrage_start(&self, round: usize) -> Option<&Enrage> {
+        self.enrage.as_ref().filter(|x| x.round == round)
+    }
+}
+
+/// What an enemy ship does once its health runs out.
+#[derive(Reflect, Serialize, Deserialize, Copy, Clone, Default, Eq, PartialEq, Debug)]
+pub enum Defeat {
+    /// Survive on 1 health and flee off-screen.
+    #[default]
+    Flee,
+    /// Shake apart and explode.
+    Explode,
+}
+
+#[derive(Reflect, Serialize, Deserialize, Clone, Default, Debug)]
+#[serde(deny_unknown_fields, default)]
+pub struct BossPhase {
+    /// Enter this phase at the start of an enemy phase once health drops to this
+    /// fraction of max health.
+    pub health: f32,
+    /// The deck to fire from this phase onwards.
+    pub deck: EnemyDeck,
+    /// A toast shown when the phase begins.
+    pub message: String,
+}
+
+#[derive(Reflect, Serialize, Deserialize, Clone, Default, Debug)]
+#[serde(deny_unknown_fields, default)]
+pub struct Enrage {
+    /// The round the ship becomes enraged.
+    pub round: usize,
+    /// Extra flux added to each of the ship's actions while enraged.
+    pub flux: f32,
+    /// A toast shown when the ship becomes enraged.
+    pub message: String,
+}
@@ -3,10 +3,14 @@ use crate::combat::death::DieOnLifetime;
 use crate::combat::death::FadeOutOnDeath;
 use crate::combat::faction::Faction;
 use crate::combat::health::Health;
+use crate::combat::shield::Armor;
+use crate::combat::shield::Shield;
+use crate::combat::status::StatusEffects;
 use crate::core::audio::AudioSettings;
 use crate::core::audio::sfx_audio;
 use crate::level::Level;
 use crate::prelude::*;
+use crate::rng::GameRng;
 use crate::screen::gameplay::GameplayAssets;
 use crate::ship::EnemyShip;
 use crate::ship::PlayerShip;
@@ -90,10 +94,37 @@ fn deal_damage_on_collision(
 
 fn reduce_health_on_damage(
     trigger: Trigger<OnDamage>,
-    mut health_query: Query<&mut Health, Without<Dead /* Stop! He's already dead! */>>,
+    mut health_query: Query<
+        (
+            &mut Health,
+            Option<&mut Shield>,
+            Option<&Armor>,
+            Option<&StatusEffects>,
+        ),
+        Without<Dead /* Stop! He's already dead! */>,
+    >,
 ) {
     let target = r!(trigger.get_target());
-    rq!(health_query.get_mut(target)).current -= trigger.0;
+    let (mut health, mut shield, armor, statuses) = rq!(health_query.get_mut(target));
+    health.current -= mitigate_damage(trigger.0, statuses, shield.as_deref_mut(), armor);
+}
+
+/// The damage that gets through to health after statuses, shield and armor.
+pub fn mitigate_damage(
+    damage: f32,
+    statuses: Option<&StatusEffects>,
+    shield: Option<&mut Shield>,
+    armor: Option<&Armor>,
+) -> f32 {
+    // Statuses scale damage first, then shields absorb it, then armor reduces what gets through.
+    let mut damage = damage * statuses.map_or(1.0, StatusEffects::damage_factor);
+    if let Some(shield) = shield {
+        damage = shield.absorb(damage);
+    }
+    if let Some(armor) = armor {
+        damage = armor.reduce(damage);
+    }
+    damage
 }
 
 fn play_ship_hurt_sfx_on_damage(
@@ -126,15 +157,16 @@ fn spawn_damage_popup_on_damage(
     images: Res<Assets<Image>>,
     player_ship: Single<Entity, With<PlayerShip>>,
     player_ship_body: Single<(&Sprite, &GlobalTransform), With<PlayerShipBody>>,
-    enemy_ship: Single<(Entity, &Sprite, &Transform), With<EnemyShip>>,
+    enemy_query: Query<(&Sprite, &Transform), With<EnemyShip>>,
+    mut game_rng: ResMut<GameRng>,
 ) {
     let target = r!(trigger.get_target());
     let damage_config = r!(damage_config.get());
 
     let (sprite, mut transform) = if target == *player_ship {
         (player_ship_body.0, player_ship_body.1.compute_transform())
-    } else if target == enemy_ship.0 {
-        (enemy_ship.1, *enemy_ship.2)
+    } else if let Ok((sprite, transform)) = enemy_query.get(target) {
+        (sprite, *transform)
     } else {
         warn!("No match found for entity.");
         return;
@@ -142,8 +174,7 @@ fn spawn_damage_popup_on_damage(
     let sprite_size = r!(images.get(&sprite.image)).size_f32();
 
     // Randomize position.
-    let rng = &mut thread_rng();
-    let point = Rectangle::from_size(sprite_size).sample_interior(rng);
+    let point = Rectangle::from_size(sprite_size).sample_interior(&mut game_rng.cosmetic);
     transform.translation += point.extend(5.0);
 
     // Scale with number.
@@ -2,8 +2,11 @@ use crate::combat::death::Dead;
 use crate::combat::death::DieOnLifetime;
 use crate::combat::death::FadeOutOnDeath;
 use crate::combat::death::OnDeath;
+use crate::combat::shield::Shield;
+use crate::combat::shield::ShieldBarFill;
 use crate::level::Level;
 use crate::prelude::*;
+use crate::rng::GameRng;
 
 pub fn plugin(app: &mut App) {
     app.configure::<(
@@ -40,6 +43,16 @@ pub fn health_bar(offset: Vec2, size: Vec2) -> impl Bundle {
                 Sprite::default(),
                 Transform::from_scale(size.extend(1.0)),
             ),
+            (
+                Name::new("ShieldBarFill"),
+                ShieldBarFill,
+                Sprite::default(),
+                Transform::from_translation(vec3(0.0, -0.75 * size.y, 0.1)).with_scale(vec3(
+                    size.x,
+                    0.5 * size.y,
+                    1.0
+                )),
+            ),
         ],
     )
 }
@@ -48,6 +61,7 @@ pub fn health_bar(offset: Vec2, size: Vec2) -> impl Bundle {
 #[serde(deny_unknown_fields, default)]
 pub struct HealthConfig {
     health_bar_color_ramp: Vec<Color>,
+    pub shield_bar_color: Color,
     heal_popup_font_size: f32,
     heal_popup_font_color: Color,
     heal_popup_offset: Vec2,
@@ -163,14 +177,19 @@ impl Configure for HealthBarLabel {
 }
 
 fn sync_health_label(
-    health_query: Query<&Health>,
+    health_query: Query<(&Health, Option<&Shield>)>,
     parent_query: Query<&ChildOf>,
     mut label_query: Query<(&ChildOf, &mut Text2d), With<HealthBarLabel>>,
 ) {
     for (child_of, mut text) in &mut label_query {
         let grandchild_of = c!(parent_query.get(child_of.parent()));
-        let health = c!(health_query.get(grandchild_of.parent()));
-        text.0 = health.current.to_string();
+        let (health, shield) = c!(health_query.get(grandchild_of.parent()));
+        let shield = shield.map_or(0.0, |x| x.current);
+        text.0 = if shield > f32::EPSILON {
+            format!("{} (+{})", health.current, (10.0 * shield).round() / 10.0)
+        } else {
+            health.current.to_string()
+        };
     }
 }
 
@@ -206,12 +225,13 @@ fn spawn_heal_popup_on_heal(
     mut commands: Commands,
     health_config: ConfigRef<HealthConfig>,
     transform_query: Query<&Transform>,
+    mut game_rng: ResMut<GameRng>,
 ) {
     let target = r!(trigger.get_target());
     let health_config = r!(health_config.get());
 
     // Randomize position.
-    let rng = &mut thread_rng();
+    let rng = &mut game_rng.cosmetic;
     let mut transform = *r!(transform_query.get(target));
     transform.translation += (health_config.heal_popup_offset
         + health_config.heal_popup_offset_spread * rng.gen_range(-1.0..=1.0))
@@ -2,6 +2,9 @@ pub mod damage;
 pub mod death;
 pub mod faction;
 pub mod health;
+pub mod shield;
+pub mod status;
+pub mod target;
 
 use crate::prelude::*;
 
@@ -11,5 +14,8 @@ pub(super) fn plugin(app: &mut App) {
         death::plugin,
         faction::plugin,
         health::plugin,
+        shield::plugin,
+        status::plugin,
+        target::plugin,
     ));
 }
@@ -0,0 +1,138 @@
+use crate::combat::health::Health;
+use crate::combat::health::HealthConfig;
+use crate::phase::Phase;
+use crate::prelude::*;
+
+pub(super) fn plugin(app: &mut App) {
+    app.configure::<(Shield, Armor, OnShield, ShieldBarFill)>();
+}
+
+/// A layer of protection that absorbs damage before [`Armor`] and [`Health`].
+#[derive(Component, Reflect, Serialize, Deserialize, Default, Clone, Debug)]
+#[reflect(Component)]
+#[serde(deny_unknown_fields, default)]
+pub struct Shield {
+    pub current: f32,
+    /// The shield regenerates up to this amount.
+    pub max: f32,
+    /// How much the shield regenerates at the end of each round.
+    pub regen: f32,
+}
+
+impl Configure for Shield {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(StateFlush, Phase::Setup.on_enter(regenerate_shields));
+    }
+}
+
+impl Shield {
+    /// A full shield with the given max and regeneration.
+    pub fn new(max: f32, regen: f32) -> Self {
+        Self {
+            current: max,
+            max,
+            regen,
+        }
+    }
+
+    /// Absorb as much of the damage as possible, returning the rest.
+    pub fn absorb(&mut self, damage: f32) -> f32 {
+        let absorbed = damage.min(self.current.max(0.0));
+        self.current -= absorbed;
+        damage - absorbed
+    }
+
+    /// Regenerate for the end of a round, up to the max.
+    pub fn regenerate(&mut self) {
+        // Don't take away shields gained above the max.
+        rq!(self.current < self.max);
+        self.current = (self.current + self.regen).min(self.max);
+    }
+}
+
+fn regenerate_shields(mut shield_query: Query<&mut Shield>) {
+    for mut shield in &mut shield_query {
+        shield.regenerate();
+    }
+}
+
+/// Reduces damage that gets through the [`Shield`].
+#[derive(Component, Reflect, Serialize, Deserialize, Default, Clone, Debug)]
+#[reflect(Component)]
+#[serde(deny_unknown_fields, default)]
+pub struct Armor {
+    /// A flat amount subtracted from each hit.
+    pub flat: f32,
+    /// A fraction of each hit ignored after the flat reduction, from 0 to 1.
+    pub percent: f32,
+}
+
+impl Configure for Armor {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+    }
+}
+
+impl Armor {
+    pub fn reduce(&self, damage: f32) -> f32 {
+        (damage - self.flat).max(0.0) * (1.0 - self.percent.clamp(0.0, 1.0))
+    }
+}
+
+#[derive(Event, Reflect, Debug)]
+pub struct OnShield(pub f32);
+
+impl Configure for OnShield {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_observer(increase_shield_on_shield);
+    }
+}
+
+fn increase_shield_on_shield(
+    trigger: Trigger<OnShield>,
+    mut commands: Commands,
+    mut shield_query: Query<&mut Shield>,
+) {
+    let ship = r!(trigger.get_target());
+    if let Ok(mut shield) = shield_query.get_mut(ship) {
+        shield.current += trigger.0;
+    } else {
+        commands.entity(ship).try_insert(Shield {
+            current: trigger.0,
+            ..default()
+        });
+    }
+}
+
+/// Reads from the [`Shield`] and [`Health`] components on its parent entity.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct ShieldBarFill;
+
+impl Configure for ShieldBarFill {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(Update, sync_shield_bar.in_set(UpdateSystems::SyncLate));
+    }
+}
+
+fn sync_shield_bar(
+    health_config: ConfigRef<HealthConfig>,
+    mut fill_query: Query<(&ChildOf, &mut Sprite), With<ShieldBarFill>>,
+    parent_query: Query<&ChildOf>,
+    ship_query: Query<(&Health, Option<&Shield>)>,
+) {
+    let health_config = r!(health_config.get());
+    for (child_of, mut sprite) in &mut fill_query {
+        let grandchild_of = c!(parent_query.get(child_of.parent()));
+        let (health, shield) = c!(ship_query.get(grandchild_of.parent()));
+
+        // Shields are measured against max health, so the bars can be compared.
+        let shield = shield.map_or(0.0, |x| x.current.max(0.0));
+        let t = (shield / health.max).min(1.0);
+        sprite.custom_size = Some(vec2(t, 1.0));
+        sprite.color = health_config.shield_bar_color;
+    }
+}
@@ -0,0 +1,107 @@
+use crate::combat::damage::OnDamage;
+use crate::combat::death::Dead;
+use crate::combat::faction::Faction;
+use crate::phase::Phase;
+use crate::prelude::*;
+use crate::stats::Stats;
+
+pub(super) fn plugin(app: &mut App) {
+    app.configure::<(DamageOverTime, StatusEffects)>();
+}
+
+/// Damage dealt at the end of each round, as a list of `(damage, rounds left)`.
+#[derive(Component, Reflect, Serialize, Deserialize, Clone, Default, Debug)]
+#[reflect(Component)]
+pub struct DamageOverTime(pub Vec<(f32, usize)>);
+
+impl Configure for DamageOverTime {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(StateFlush, Phase::Setup.on_enter(tick_damage_over_time));
+    }
+}
+
+impl DamageOverTime {
+    /// Count down each source of damage, returning the damage dealt this round.
+    pub fn tick(&mut self) -> f32 {
+        let damage = self.0.iter().map(|(damage, _)| damage).sum();
+        for (_, rounds) in &mut self.0 {
+            *rounds -= 1;
+        }
+        self.0.retain(|(_, rounds)| *rounds > 0);
+        damage
+    }
+}
+
+fn tick_damage_over_time(
+    mut commands: Commands,
+    mut dot_query: Query<(Entity, &Faction, &mut DamageOverTime), Without<Dead>>,
+    mut stats: ResMut<Stats>,
+) {
+    for (entity, faction, mut dot) in &mut dot_query {
+        let damage = dot.tick();
+        match faction {
+            Faction::Player => stats.damage_taken += damage,
+            Faction::Enemy => stats.damage_given += damage,
+        }
+        commands.entity(entity).trigger(OnDamage(damage));
+        if dot.0.is_empty() {
+            commands.entity(entity).remove::<DamageOverTime>();
+        }
+    }
+}
+
+/// Named statuses applied by actions, with the number of rounds left on each.
+///
+/// Statuses are counted down at the end of each round and can be checked with
+/// [`StatusEffects::has`].
+#[derive(Component, Reflect, Serialize, Deserialize, Clone, Default, Debug)]
+#[reflect(Component)]
+pub struct StatusEffects(pub HashMap<String, usize>);
+
+impl Configure for StatusEffects {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(StateFlush, Phase::Setup.on_enter(tick_status_effects));
+    }
+}
+
+impl StatusEffects {
+    /// A status that makes the ship take more damage.
+    pub const VULNERABLE: &'static str = "vulnerable";
+    /// The factor damage is scaled by while [`Self::VULNERABLE`].
+    pub const VULNERABLE_FACTOR: f32 = 1.5;
+
+    /// Apply a status, keeping the longer duration if it's already applied.
+    pub fn apply(&mut self, name: String, rounds: usize) {
+        let entry = self.0.entry(name).or_default();
+        *entry = (*entry).max(rounds);
+    }
+
+    pub fn has(&self, name: &str) -> bool {
+        self.0.contains_key(name)
+    }
+
+    /// Count down each status, removing the ones that ran out.
+    pub fn tick(&mut self) {
+        for rounds in self.0.values_mut() {
+            *rounds = rounds.saturating_sub(1);
+        }
+        self.0.retain(|_, rounds| *rounds > 0);
+    }
+
+    /// The factor to scale incoming damage by.
+    pub fn damage_factor(&self) -> f32 {
+        if self.has(Self::VULNERABLE) {
+            Self::VULNERABLE_FACTOR
+        } else {
+            1.0
+        }
+    }
+}
+
+fn tick_status_effects(mut status_query: Query<&mut StatusEffects>) {
+    for mut statuses in &mut status_query {
+        statuses.tick();
+    }
+}
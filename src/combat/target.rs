@@ -0,0 +1,56 @@
+use crate::prelude::*;
+
+pub(super) fn plugin(app: &mut App) {
+    app.configure::<(EnemyIndex, PlayerTarget, SelectTarget)>();
+}
+
+/// The index of an enemy ship in its wave.
+#[derive(Component, Reflect, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Debug)]
+#[reflect(Component)]
+pub struct EnemyIndex(pub usize);
+
+impl Configure for EnemyIndex {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+    }
+}
+
+/// The index of the enemy ship the player's chain fires at.
+#[derive(Resource, Reflect, Serialize, Deserialize, Default, Clone, Debug)]
+#[reflect(Resource)]
+pub struct PlayerTarget(pub usize);
+
+impl Configure for PlayerTarget {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.init_resource::<Self>();
+    }
+}
+
+impl PlayerTarget {
+    /// Find the targeted enemy ship, or the first living enemy ship if the target is gone.
+    pub fn find<'a>(
+        &self,
+        enemies: impl IntoIterator<Item = (Entity, &'a EnemyIndex, bool)>,
+    ) -> Option<Entity> {
+        enemies
+            .into_iter()
+            .min_by_key(|&(_, idx, is_dead)| (is_dead, idx.0 != self.0, *idx))
+            .map(|(entity, ..)| entity)
+    }
+}
+
+/// Target the enemy ship at this index.
+#[derive(Event, Reflect, Debug)]
+pub struct SelectTarget(pub usize);
+
+impl Configure for SelectTarget {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_observer(select_target);
+    }
+}
+
+fn select_target(trigger: Trigger<SelectTarget>, mut player_target: ResMut<PlayerTarget>) {
+    player_target.0 = trigger.0;
+}
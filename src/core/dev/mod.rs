@@ -9,6 +9,7 @@ mod state;
 mod ui;
 
 use crate::prelude::*;
+use crate::rng::GameRng;
 use crate::screen::Screen;
 
 pub(super) fn plugin(app: &mut App) {
@@ -44,6 +45,9 @@ struct DevConfig {
     pub log_state_flush: bool,
     pub extend_loading_menu: f32,
     pub initial_screen: Option<Screen>,
+
+    // Gameplay:
+    pub fixed_seed: Option<u64>,
 }
 
 impl Default for DevConfig {
@@ -56,6 +60,8 @@ impl Default for DevConfig {
             log_state_flush: true,
             extend_loading_menu: 0.0,
             initial_screen: None,
+
+            fixed_seed: None,
         }
     }
 }
@@ -66,6 +72,9 @@ impl Config for DevConfig {
     fn on_load(&mut self, world: &mut World) {
         diagnostics::on_load(self, world);
         state::on_load(self, world);
+        if let Some(mut game_rng) = world.get_resource_mut::<GameRng>() {
+            game_rng.fixed_seed = self.fixed_seed;
+        }
     }
 }
 
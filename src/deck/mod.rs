@@ -0,0 +1,688 @@
+pub mod policy;
+
+use std::collections::VecDeque;
+
+use crate::combat::shield::Armor;
+use crate::combat::shield::Shield;
+use crate::deck::policy::PolicyKind;
+use crate::module::AdjacencyEffect;
+use crate::module::Condition;
+use crate::module::Module;
+use crate::module::ModuleConfig;
+use crate::module::ModuleStatus;
+use crate::prelude::*;
+
+pub(super) fn plugin(app: &mut App) {
+    app.configure::<(
+        ConfigHandle<DeckConfig>,
+        PlayerDeck,
+        SelectedDeck,
+        EnemyDeck,
+    )>();
+}
+
+#[derive(Asset, Reflect, Serialize, Deserialize, Default, Debug)]
+#[serde(deny_unknown_fields, default)]
+pub struct DeckConfig {
+    pub player_decks: Vec<PlayerDeck>,
+    /// Ship sprites by path.
+    #[serde(skip)]
+    pub sprites: HashMap<String, Handle<Image>>,
+}
+
+impl Config for DeckConfig {
+    const FILE: &'static str = "deck.ron";
+
+    fn on_load(&mut self, world: &mut World) {
+        let asset_server = world.resource::<AssetServer>();
+        for deck in &self.player_decks {
+            self.sprites
+                .entry(deck.sprite.clone())
+                .or_insert_with(|| asset_server.load(&deck.sprite));
+        }
+    }
+
+    fn count_progress(&self, asset_server: &AssetServer) -> Progress {
+        let mut progress = true.into();
+        for sprite in self.sprites.values() {
+            progress += asset_server.is_loaded_with_dependencies(sprite).into();
+        }
+        progress
+    }
+}
+
+impl DeckConfig {
+    pub fn sprite(&self, path: &str) -> Handle<Image> {
+        self.sprites.get(path).cloned().unwrap_or_default()
+    }
+}
+
+#[derive(Resource, Reflect, Serialize, Deserialize, Default, Clone, Debug)]
+#[reflect(Resource)]
+#[serde(deny_unknown_fields, default)]
+pub struct PlayerDeck {
+    // Ship:
+    pub name: String,
+    /// The path to the ship's sprite.
+    pub sprite: String,
+    /// Weapon positions relative to the ship's sprite.
+    pub weapon_mounts: Vec<Vec2>,
+    /// The size of the ship's rectangular collider.
+    pub collider: Vec2,
+    pub max_health: f32,
+    /// Missing hull health carried over from the previous level.
+    pub hull_damage: f32,
+    pub heat_capacity: f32,
+    pub hand_size: usize,
+    pub weapons: Vec<Module>,
+    /// The keys of the ship's relics in the relic config.
+    pub relics: Vec<String>,
+
+    // Modules:
+    pub storage: Vec<Module>,
+    pub hand: Vec<Module>,
+    pub reactor: Vec<Module>,
+
+    // Helm:
+    pub hand_idx: usize,
+    /// The reactor slot selected with the keyboard or gamepad.
+    pub slot_idx: usize,
+    /// The reactor slot picked up to swap with the selected slot.
+    pub held_slot: Option<usize>,
+    pub just_used_storage: bool,
+
+    // Reactor:
+    pub flux: f32,
+    /// Extra flux added on top of the chain, such as from relics.
+    pub bonus_flux: f32,
+    pub chain: f32,
+    pub action_queue: VecDeque<usize>,
+    pub last_action: String,
+    pub last_touched_idx: Option<usize>,
+    /// Damage the ship will take from modules triggered while overheated.
+    pub meltdown: f32,
+    /// Extra heat each module activation generates, from the level's environment.
+    pub extra_heat: f32,
+}
+
+impl Configure for PlayerDeck {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.init_resource::<Self>();
+    }
+}
+
+/// The index of the player deck to start new runs with.
+#[derive(Resource, Reflect, Default, Debug)]
+#[reflect(Resource)]
+pub struct SelectedDeck(pub usize);
+
+impl Configure for SelectedDeck {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.init_resource::<Self>();
+    }
+}
+
+impl PlayerDeck {
+    /// Reset deck.
+    pub fn reset(&mut self) {
+        // Discard modules from reactor / hand to storage.
+        for idx in 0..self.reactor.len() {
+            self.discard_module(idx);
+        }
+        self.storage.append(&mut self.hand);
+
+        // Reset turn-based state.
+        self.held_slot = None;
+        self.just_used_storage = false;
+        self.flux = 0.0;
+        self.bonus_flux = 0.0;
+        self.chain = 0.0;
+        self.action_queue.clear();
+        self.last_action.clear();
+        self.last_touched_idx = None;
+        self.meltdown = 0.0;
+
+        // Prepare weapons and select the middle module.
+        let weapons = self.weapons.clone();
+        for weapon in weapons {
+            let draw_idx = cq!(self
+                .storage
+                .iter()
+                .position(|x| x.condition == weapon.condition && x.effect == weapon.effect));
+            self.draw_from_idx(draw_idx);
+        }
+        self.hand_idx = self.hand.len() / 2;
+    }
+
+    /// Advance the selected module index by the given step.
+    pub fn advance_selected(&mut self, step: isize) {
+        self.hand_idx = self
+            .hand_idx
+            .saturating_add_signed(step)
+            .min(self.hand.len().saturating_sub(1));
+    }
+
+    /// Advance the selected reactor slot index by the given step.
+    pub fn advance_slot(&mut self, step: isize) {
+        self.slot_idx = self
+            .slot_idx
+            .saturating_add_signed(step)
+            .min(self.reactor.len().saturating_sub(1));
+    }
+
+    /// Draw the next module from storage to hand.
+    pub fn draw_random(&mut self, rng: &mut impl Rng) {
+        rq!(!self.storage.is_empty());
+        let draw_idx = rng.gen_range(0..self.storage.len());
+        self.draw_from_idx(draw_idx);
+    }
+
+    /// Draw the specified module from storage to hand.
+    fn draw_from_idx(&mut self, idx: usize) {
+        let draw = self.storage.swap_remove(idx);
+        self.hand.push(draw);
+        self.just_used_storage = true;
+    }
+
+    /// Discard a module from the reactor to storage.
+    pub fn discard_module(&mut self, idx: usize) {
+        rq!(!matches!(self.reactor[idx].status, ModuleStatus::SlotEmpty));
+
+        let mut module = Module::EMPTY;
+        core::mem::swap(&mut self.reactor[idx], &mut module);
+        module.status = ModuleStatus::FaceUp;
+        module.heat = 0.0;
+        module.boost = 0.0;
+        self.last_touched_idx = Some(idx);
+        self.storage.push(module);
+        self.just_used_storage = true;
+    }
+
+    /// Find the next available reactor slot to place a module in.
+    fn next_available_slot(&self) -> Option<usize> {
+        self.reactor
+            .iter()
+            .position(|slot| matches!(slot.status, ModuleStatus::SlotEmpty))
+    }
+
+    /// Try to play the currently selected module from hand to reactor,
+    /// returning false if it's not possible.
+    pub fn play_selected(&mut self) -> bool {
+        rq!(!self.hand.is_empty() && !self.reactor.is_empty());
+        let slot_idx = rq!(self.next_available_slot());
+        self.play_into_slot(self.hand_idx, slot_idx)
+    }
+
+    /// Try to play a module from hand into a specific reactor slot, discarding the module
+    /// that was there, returning false if it's not possible.
+    pub fn play_into_slot(&mut self, hand_idx: usize, slot_idx: usize) -> bool {
+        rq!(hand_idx < self.hand.len() && slot_idx < self.reactor.len());
+
+        // Remove the module from hand.
+        let mut selected = self.hand.remove(hand_idx);
+        // Keep the same module selected if it was after the one removed.
+        if hand_idx < self.hand_idx {
+            self.hand_idx -= 1;
+        }
+        self.hand_idx = self.hand_idx.clamp(0, self.hand.len().saturating_sub(1));
+
+        // Place it in the reactor slot.
+        self.discard_module(slot_idx);
+        selected.status = ModuleStatus::SlotInactive;
+        self.reactor[slot_idx] = selected;
+        self.last_touched_idx = Some(slot_idx);
+
+        true
+    }
+
+    /// Try to swap the modules in two reactor slots, returning false if it's not possible.
+    pub fn swap_slots(&mut self, a: usize, b: usize) -> bool {
+        rq!(a != b && a < self.reactor.len() && b < self.reactor.len());
+        self.reactor.swap(a, b);
+        self.last_touched_idx = Some(b);
+        true
+    }
+
+    /// Try to discard the currently selected module from hand to storage,
+    /// returning false if it's not possible.
+    pub fn discard_selected(&mut self) -> bool {
+        rq!(!self.hand.is_empty());
+
+        // Remove selected module from hand.
+        let idx = self.hand_idx;
+        let selected = self.hand.remove(idx);
+        self.hand_idx = self.hand_idx.clamp(0, self.hand.len().saturating_sub(1));
+
+        // Insert it into storage.
+        self.storage.push(selected);
+        self.just_used_storage = true;
+
+        true
+    }
+
+    /// Find the next matching reactor module to trigger.
+    pub fn next_matching_module(&self, module_config: &ModuleConfig) -> Option<usize> {
+        let meltdown = module_config.heat.meltdown_damage.is_some();
+        let can_trigger = |slot: &Module| match slot.status {
+            ModuleStatus::SlotInactive => true,
+            ModuleStatus::SlotOverheated => meltdown,
+            _ => false,
+        };
+
+        // The chain may be limited to the neighbors of the last triggered module.
+        let chain_from = self.action_queue.back().copied().filter(|&idx| {
+            module_config
+                .action(&self.reactor[idx].effect)
+                .adjacency
+                .iter()
+                .any(|x| matches!(x, AdjacencyEffect::ChainAdjacent))
+        });
+        let is_reachable = |idx: usize| {
+            chain_from.is_none_or(|from| self.neighbors(module_config, from).any(|x| x == idx))
+        };
+
+        self.reactor
+            .iter()
+            .enumerate()
+            .position(|(idx, slot)| {
+                can_trigger(slot)
+                    && is_reachable(idx)
+                    && Condition::parse(&slot.condition).is_met(
+                        &self.last_action,
+                        self.chain,
+                        self.flux,
+                    )
+            })
+            .or_else(|| {
+                self.reactor
+                    .iter()
+                    .position(|slot| can_trigger(slot) && slot.condition.is_empty())
+            })
+    }
+
+    /// Determine whether the reactor is done powering up.
+    pub fn is_reactor_done(&self, module_config: &ModuleConfig) -> bool {
+        self.next_matching_module(module_config).is_none()
+    }
+
+    /// Simulate one step powering up the reactor and return false if done.
+    pub fn step_reactor(&mut self, module_config: &ModuleConfig) -> bool {
+        // Activate the first matching module.
+        let Some(idx) = self.next_matching_module(module_config) else {
+            self.last_action.clear();
+            self.last_touched_idx = None;
+            return false;
+        };
+
+        // Sum the boosts from neighboring modules.
+        let neighbors = self
+            .neighbors(module_config, idx)
+            .filter(|&x| !matches!(self.reactor[x].status, ModuleStatus::SlotEmpty))
+            .collect::<Vec<_>>();
+        let boost = neighbors
+            .iter()
+            .flat_map(|&x| &module_config.action(&self.reactor[x].effect).adjacency)
+            .map(|x| match x {
+                AdjacencyEffect::Boost(x) => *x,
+                _ => 0.0,
+            })
+            .sum::<f32>();
+
+        let slot = &mut self.reactor[idx];
+        let condition_heat = Condition::parse(&slot.condition).heat(module_config);
+        let effect = &module_config.action(&slot.effect);
+
+        // Triggering an overheated module is only possible with meltdowns enabled.
+        if matches!(slot.status, ModuleStatus::SlotOverheated) {
+            self.meltdown += module_config.heat.meltdown_damage.unwrap_or_default();
+        }
+        slot.status = ModuleStatus::SlotActive;
+        slot.boost = boost;
+        if slot.condition.is_empty() {
+            self.chain = 0.0;
+        }
+        self.chain += 1.0;
+        let heat =
+            (self.chain + condition_heat + effect.effect_heat + self.extra_heat).max(-slot.heat);
+        slot.heat += heat;
+        self.flux = self.flux.max(self.chain + self.bonus_flux);
+        self.action_queue.push_back(idx);
+        self.last_action = slot.effect.clone();
+        self.last_touched_idx = Some(idx);
+
+        // Share the heat gained with the neighboring modules.
+        let share = effect
+            .adjacency
+            .iter()
+            .map(|x| match x {
+                AdjacencyEffect::ShareHeat(x) => *x,
+                _ => 0.0,
+            })
+            .sum::<f32>()
+            .clamp(0.0, 1.0);
+        if share > 0.0 && heat > 0.0 && !neighbors.is_empty() {
+            let shared = share * heat;
+            slot.heat -= shared;
+            for &neighbor in &neighbors {
+                self.reactor[neighbor].heat += shared / neighbors.len() as f32;
+            }
+        }
+
+        // Cool the neighboring modules.
+        let cooling = effect.effect_cooling;
+        if cooling > 0.0 {
+            for &neighbor in &neighbors {
+                self.cool_slot(neighbor, cooling);
+            }
+        }
+
+        true
+    }
+
+    /// The indices of the reactor slots adjacent to the given slot in the reactor grid.
+    pub fn neighbors(
+        &self,
+        module_config: &ModuleConfig,
+        idx: usize,
+    ) -> impl Iterator<Item = usize> + use<> {
+        let len = self.reactor.len();
+        let columns = module_config.reactor_columns();
+        let col = idx % columns;
+        [
+            idx.checked_sub(columns),
+            (col > 0).then(|| idx - 1),
+            (col + 1 < columns).then_some(idx + 1),
+            Some(idx + columns),
+        ]
+        .into_iter()
+        .flatten()
+        .filter(move |&x| x < len)
+    }
+
+    /// Remove heat from a reactor slot, recovering it if it was overheated and is now
+    /// within the heat capacity.
+    pub fn cool_slot(&mut self, idx: usize, amount: f32) {
+        let heat_capacity = self.heat_capacity;
+        let slot = r!(self.reactor.get_mut(idx));
+        rq!(!matches!(slot.status, ModuleStatus::SlotEmpty));
+        slot.heat = (slot.heat - amount).max(0.0);
+        if matches!(slot.status, ModuleStatus::SlotOverheated) && slot.heat <= heat_capacity {
+            slot.status = ModuleStatus::SlotInactive;
+        }
+    }
+
+    /// Dissipate heat from every reactor slot at the end of a round.
+    pub fn dissipate_heat(&mut self, module_config: &ModuleConfig) {
+        let dissipation = module_config.heat.dissipation;
+        rq!(dissipation > 0.0);
+        for idx in 0..self.reactor.len() {
+            self.cool_slot(idx, dissipation);
+        }
+    }
+
+    /// Predict the result of powering up the reactor, without changing the deck.
+    pub fn forecast(&self, module_config: &ModuleConfig) -> ReactorForecast {
+        let mut deck = self.clone();
+        while deck.step_reactor(module_config) {}
+
+        let order = deck.action_queue.iter().copied().collect::<Vec<_>>();
+        ReactorForecast {
+            flux: deck.flux,
+            meltdown: deck.meltdown,
+            heat: deck.reactor.iter().map(|x| x.heat).collect(),
+            overheated: (0..deck.reactor.len())
+                .map(|idx| order.contains(&idx) && deck.reactor[idx].heat > deck.heat_capacity)
+                .collect(),
+            order,
+        }
+    }
+
+    /// Determine whether the player is done attacking.
+    pub fn is_player_done(&self) -> bool {
+        self.action_queue.is_empty()
+    }
+
+    /// Take one step through the player's attack, returning the action or `None` if done.
+    pub fn step_player(&mut self) -> Option<String> {
+        self.last_touched_idx = self.action_queue.pop_front();
+        if let Some(idx) = self.last_touched_idx {
+            // Deactivate the reactor module and return its action.
+            self.reactor[idx].status = if self.reactor[idx].heat > self.heat_capacity {
+                ModuleStatus::SlotOverheated
+            } else {
+                ModuleStatus::SlotInactive
+            };
+            Some(self.reactor[idx].effect.clone())
+        } else {
+            // Action queue is done, so reset chain and flux.
+            self.chain = 0.0;
+            self.flux = 0.0;
+            self.bonus_flux = 0.0;
+            None
+        }
+    }
+
+    /// The number of modules the next setup phase will draw from storage.
+    pub fn next_draw_count(&self) -> usize {
+        self.hand_size
+            .saturating_sub(self.hand.len())
+            .min(self.storage.len())
+    }
+
+    /// The chance that the next setup phase draws at least one matching module from storage.
+    pub fn draw_chance(&self, is_match: impl Fn(&Module) -> bool) -> f32 {
+        let total = self.storage.len();
+        let misses = self.storage.iter().filter(|x| !is_match(x)).count();
+
+        // Draws are without replacement, so multiply the chance to miss on each draw.
+        let mut miss_chance = 1.0;
+        for i in 0..self.next_draw_count() {
+            miss_chance *= misses.saturating_sub(i) as f32 / (total - i) as f32;
+        }
+
+        1.0 - miss_chance
+    }
+
+    /// Determine whether setting up the helm is done.
+    pub fn is_setup_done(&self) -> bool {
+        self.storage.is_empty() || self.hand.len() >= self.hand_size
+    }
+
+    /// Steps setting up the helm, returning false if setup was already complete.
+    pub fn step_setup(&mut self, rng: &mut impl Rng) -> bool {
+        if !self.storage.is_empty() && self.hand.len() < self.hand_size {
+            self.draw_random(rng);
+        } else {
+            return false;
+        }
+
+        true
+    }
+}
+
+/// The predicted result of powering up the reactor.
+#[derive(Reflect, Clone, Default, PartialEq, Debug)]
+pub struct ReactorForecast {
+    /// The reactor indices in the order they will activate.
+    pub order: Vec<usize>,
+    /// The flux at the end of the reactor phase.
+    pub flux: f32,
+    /// The damage the ship will take from meltdowns.
+    pub meltdown: f32,
+    /// The heat of each reactor slot at the end of the reactor phase.
+    pub heat: Vec<f32>,
+    /// Whether each reactor slot will overheat.
+    pub overheated: Vec<bool>,
+}
+
+#[derive(Component, Reflect, Serialize, Deserialize, Clone, Debug)]
+#[reflect(Component)]
+#[serde(deny_unknown_fields, default)]
+pub struct EnemyDeck {
+    /// The name shown when choosing a target, or empty to number the ship instead.
+    pub name: String,
+    pub max_health: f32,
+    /// The shield the ship starts each level with.
+    pub shield: Option<Shield>,
+    pub armor: Option<Armor>,
+
+    /// A list of actions that are always performed at the beginning of the enemy attack.
+    pub start: Vec<String>,
+    /// A list of actions that are performed based on the current round.
+    pub volley: Vec<String>,
+    /// How much deeper the ship will go into its volley each round.
+    pub volley_rate: usize,
+    /// A list of actions that are always performed at the end of the enemy attack.
+    pub finish: Vec<String>,
+    /// A reactor the enemy plays each round instead of its scripted action lists.
+    pub reactor: Option<EnemyReactor>,
+
+    pub flux: f32,
+    pub action_idx: usize,
+}
+
+impl Configure for EnemyDeck {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+    }
+}
+
+impl Default for EnemyDeck {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            max_health: 0.0,
+            shield: None,
+            armor: None,
+            start: vec![],
+            volley: vec![],
+            volley_rate: 1,
+            finish: vec![],
+            reactor: None,
+            flux: 0.0,
+            action_idx: 0,
+        }
+    }
+}
+
+impl EnemyDeck {
+    /// The name of the ship, given its index in its wave.
+    pub fn display_name(&self, idx: usize) -> String {
+        if self.name.is_empty() {
+            format!("Enemy {}", idx + 1)
+        } else {
+            self.name.clone()
+        }
+    }
+
+    /// Determine whether the deck is done yielding actions.
+    pub fn is_done(&self, round: usize) -> bool {
+        if let Some(reactor) = &self.reactor {
+            return reactor.deck.is_player_done();
+        }
+
+        self.action_idx
+            >= self.start.len()
+                + self.volley.len().min(self.volley_rate * round)
+                + self.finish.len()
+    }
+
+    /// Play the enemy's hand into its reactor and power it up, returning the meltdown damage.
+    ///
+    /// Does nothing for scripted enemies.
+    pub fn play_turn(&mut self, module_config: &ModuleConfig, rng: &mut impl Rng) -> f32 {
+        let reactor = rq!(self.reactor.as_mut());
+        let deck = &mut reactor.deck;
+
+        // Setup.
+        deck.dissipate_heat(module_config);
+        while deck.step_setup(rng) {}
+
+        // Helm.
+        let mut policy = reactor.ai.build(rng.r#gen());
+        for _ in 0..EnemyReactor::MAX_HELM_MOVES {
+            if !policy.decide(deck, module_config).perform(deck) {
+                break;
+            }
+        }
+
+        // Reactor.
+        while deck.step_reactor(module_config) {}
+        self.flux = deck.flux;
+
+        std::mem::take(&mut deck.meltdown)
+    }
+
+    /// Simulate one step and get the next action.
+    pub fn step(&mut self, round: usize) -> Option<String> {
+        if let Some(reactor) = &mut self.reactor {
+            let action = reactor.deck.step_player();
+            if action.is_none() {
+                self.flux = 0.0;
+            }
+            return action;
+        }
+
+        if let Some(action) = self
+            .start
+            .iter()
+            .chain(self.volley.iter().take(self.volley_rate * round))
+            .chain(&self.finish)
+            .nth(self.action_idx)
+        {
+            self.action_idx += 1;
+            self.flux += 1.0;
+            Some(action.clone())
+        } else {
+            self.action_idx = 0;
+            self.flux = 0.0;
+            None
+        }
+    }
+
+    /// Predict the actions the enemy will fire this round and the flux of each, without
+    /// changing the deck.
+    ///
+    /// Effects that change the enemy's flux mid-attack are not accounted for.
+    pub fn intent(
+        &self,
+        round: usize,
+        module_config: &ModuleConfig,
+        rng: &mut impl Rng,
+    ) -> Vec<(String, f32)> {
+        let mut deck = self.clone();
+        deck.play_turn(module_config, rng);
+
+        let mut intent = vec![];
+        while let Some(action) = deck.step(round) {
+            intent.push((action, deck.flux + deck.boost()));
+        }
+        intent
+    }
+
+    /// The flux boost from neighboring reactor modules for the last action.
+    pub fn boost(&self) -> f32 {
+        let reactor = rq!(self.reactor.as_ref());
+        reactor
+            .deck
+            .last_touched_idx
+            .map_or(0.0, |idx| reactor.deck.reactor[idx].boost)
+    }
+}
+
+/// An enemy reactor that follows the same rules as the player's.
+#[derive(Reflect, Serialize, Deserialize, Default, Clone, Debug)]
+#[serde(deny_unknown_fields, default)]
+pub struct EnemyReactor {
+    /// The strategy used to play the enemy's hand.
+    pub ai: PolicyKind,
+    /// The enemy's modules, reactor slots, hand size and heat capacity.
+    pub deck: PlayerDeck,
+}
+
+impl EnemyReactor {
+    /// End the helm phase after this many moves.
+    const MAX_HELM_MOVES: usize = 50;
+}
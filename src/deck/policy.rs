@@ -0,0 +1,248 @@
+//! Bot strategies for the helm phase.
+
+use crate::deck::PlayerDeck;
+use crate::module::Condition;
+use crate::module::ModuleConfig;
+use crate::module::ModuleStatus;
+use crate::prelude::*;
+
+/// A single player input during the helm phase.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum HelmMove {
+    /// Play the module at this hand index into the reactor.
+    Play(usize),
+    /// Discard the module at this hand index to storage.
+    Discard(usize),
+    /// Remove the module at this reactor index to storage.
+    Remove(usize),
+    EndTurn,
+}
+
+impl HelmMove {
+    /// Perform the move on the player deck, returning false if the turn is over.
+    pub fn perform(self, player_deck: &mut PlayerDeck) -> bool {
+        match self {
+            Self::Play(idx) => {
+                rq!(idx < player_deck.hand.len());
+                player_deck.hand_idx = idx;
+                player_deck.play_selected()
+            },
+            Self::Discard(idx) => {
+                rq!(idx < player_deck.hand.len());
+                player_deck.hand_idx = idx;
+                player_deck.discard_selected()
+            },
+            Self::Remove(idx) => {
+                let slot = rq!(player_deck.reactor.get(idx));
+                rq!(!matches!(slot.status, ModuleStatus::SlotEmpty));
+                player_deck.discard_module(idx);
+                true
+            },
+            Self::EndTurn => false,
+        }
+    }
+}
+
+/// A strategy that decides what to do during the helm phase.
+pub trait HelmPolicy {
+    /// Decide the next move given the current state of the player deck.
+    fn decide(&mut self, player_deck: &PlayerDeck, module_config: &ModuleConfig) -> HelmMove;
+}
+
+#[derive(Reflect, Serialize, Deserialize, Copy, Clone, Default, Eq, PartialEq, Debug)]
+pub enum PolicyKind {
+    Random,
+    GreedyMaxFlux,
+    ChainSeeker,
+    #[default]
+    HeatAware,
+}
+
+impl PolicyKind {
+    pub const ALL: [Self; 4] = [
+        Self::Random,
+        Self::GreedyMaxFlux,
+        Self::ChainSeeker,
+        Self::HeatAware,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Random => "random",
+            Self::GreedyMaxFlux => "greedy-max-flux",
+            Self::ChainSeeker => "chain-seeker",
+            Self::HeatAware => "heat-aware",
+        }
+    }
+
+    pub fn build(self, seed: u64) -> Box<dyn HelmPolicy> {
+        match self {
+            Self::Random => Box::new(RandomPolicy(StdRng::seed_from_u64(seed))),
+            Self::GreedyMaxFlux => Box::new(GreedyMaxFluxPolicy),
+            Self::ChainSeeker => Box::new(ChainSeekerPolicy),
+            Self::HeatAware => Box::new(HeatAwarePolicy),
+        }
+    }
+}
+
+/// Removes overheated modules, then plays, discards, or ends the turn at random.
+pub struct RandomPolicy(pub StdRng);
+
+impl HelmPolicy for RandomPolicy {
+    fn decide(&mut self, player_deck: &PlayerDeck, _module_config: &ModuleConfig) -> HelmMove {
+        if let Some(idx) = overheated_slot(player_deck).filter(|_| self.0.gen_bool(0.5)) {
+            return HelmMove::Remove(idx);
+        }
+
+        let mut moves = vec![HelmMove::EndTurn];
+        if has_empty_slot(player_deck) {
+            moves.extend((0..player_deck.hand.len()).map(HelmMove::Play));
+        }
+        moves.extend((0..player_deck.hand.len()).map(HelmMove::Discard));
+
+        *moves.choose(&mut self.0).unwrap_or(&HelmMove::EndTurn)
+    }
+}
+
+/// Plays whichever module results in the highest flux this turn.
+pub struct GreedyMaxFluxPolicy;
+
+impl HelmPolicy for GreedyMaxFluxPolicy {
+    fn decide(&mut self, player_deck: &PlayerDeck, module_config: &ModuleConfig) -> HelmMove {
+        if let Some(idx) = overheated_slot(player_deck) {
+            return HelmMove::Remove(idx);
+        }
+        if !has_empty_slot(player_deck) {
+            return HelmMove::EndTurn;
+        }
+
+        best_play(player_deck, |idx| {
+            let mut player_deck = player_deck.clone();
+            HelmMove::Play(idx).perform(&mut player_deck);
+            player_deck.forecast(module_config).flux
+        })
+        .map_or(HelmMove::EndTurn, HelmMove::Play)
+    }
+}
+
+/// Plays whichever module links up with the most modules already in the reactor.
+pub struct ChainSeekerPolicy;
+
+impl HelmPolicy for ChainSeekerPolicy {
+    fn decide(&mut self, player_deck: &PlayerDeck, module_config: &ModuleConfig) -> HelmMove {
+        if let Some(idx) = overheated_slot(player_deck) {
+            return HelmMove::Remove(idx);
+        }
+        if !has_empty_slot(player_deck) {
+            return HelmMove::EndTurn;
+        }
+
+        let slots = player_deck
+            .reactor
+            .iter()
+            .filter(|slot| !matches!(slot.status, ModuleStatus::SlotEmpty))
+            .collect::<Vec<_>>();
+        let has_starter = slots.iter().any(|slot| slot.condition.is_empty());
+
+        // Judge chain and flux conditions by the chain this module would extend.
+        let chain = slots.len() as f32;
+        let flux = player_deck.forecast(module_config).flux;
+
+        best_play(player_deck, |idx| {
+            let module = &player_deck.hand[idx];
+            let condition = Condition::parse(&module.condition);
+            let links = slots
+                .iter()
+                .filter(|slot| {
+                    condition.is_met(&slot.effect, chain, flux)
+                        || Condition::parse(&slot.condition).is_met(&module.effect, chain, flux)
+                })
+                .count();
+            let starts_chain = !has_starter && module.condition.is_empty();
+
+            links as f32 + if starts_chain { 2.0 } else { 0.0 }
+        })
+        .map_or(HelmMove::EndTurn, HelmMove::Play)
+    }
+}
+
+/// Trades flux for heat, removing modules before they overheat and holding back
+/// modules that would overheat others.
+pub struct HeatAwarePolicy;
+
+impl HelmPolicy for HeatAwarePolicy {
+    fn decide(&mut self, player_deck: &PlayerDeck, module_config: &ModuleConfig) -> HelmMove {
+        if let Some(idx) = overheated_slot(player_deck) {
+            return HelmMove::Remove(idx);
+        }
+
+        // Remove the hottest module if it's about to overheat.
+        let heat_capacity = player_deck.heat_capacity;
+        if let Some((idx, _)) = player_deck
+            .forecast(module_config)
+            .heat
+            .iter()
+            .enumerate()
+            .filter(|(_, heat)| **heat > heat_capacity)
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+        {
+            return HelmMove::Remove(idx);
+        }
+
+        if !has_empty_slot(player_deck) {
+            return HelmMove::EndTurn;
+        }
+
+        let score = |player_deck: &PlayerDeck| {
+            let forecast = player_deck.forecast(module_config);
+            let overheated = forecast
+                .heat
+                .iter()
+                .filter(|&&heat| heat > heat_capacity)
+                .count();
+
+            forecast.flux - 2.0 * overheated as f32
+        };
+        let current_score = score(player_deck);
+        let mut best_score = current_score;
+        let best_idx = best_play(player_deck, |idx| {
+            let mut player_deck = player_deck.clone();
+            HelmMove::Play(idx).perform(&mut player_deck);
+            let play_score = score(&player_deck);
+            best_score = best_score.max(play_score);
+            play_score
+        });
+
+        match best_idx {
+            Some(idx) if best_score >= current_score => HelmMove::Play(idx),
+            _ => HelmMove::EndTurn,
+        }
+    }
+}
+
+fn has_empty_slot(player_deck: &PlayerDeck) -> bool {
+    player_deck
+        .reactor
+        .iter()
+        .any(|slot| matches!(slot.status, ModuleStatus::SlotEmpty))
+}
+
+fn overheated_slot(player_deck: &PlayerDeck) -> Option<usize> {
+    player_deck
+        .reactor
+        .iter()
+        .position(|slot| matches!(slot.status, ModuleStatus::SlotOverheated))
+}
+
+/// Find the hand index with the highest score, preferring the leftmost on ties.
+fn best_play(player_deck: &PlayerDeck, mut score: impl FnMut(usize) -> f32) -> Option<usize> {
+    let mut best = None;
+    for idx in 0..player_deck.hand.len() {
+        let score = score(idx);
+        if best.is_none_or(|(_, best_score)| score > best_score) {
+            best = Some((idx, score));
+        }
+    }
+
+    best.map(|(idx, _)| idx)
+}
@@ -0,0 +1,334 @@
+use bevy::ecs::system::SystemId;
+
+use crate::combat::faction::Faction;
+use crate::combat::health::OnHeal;
+use crate::combat::shield::OnShield;
+use crate::combat::status::DamageOverTime;
+use crate::combat::status::StatusEffects;
+use crate::core::audio::AudioSettings;
+use crate::core::audio::sfx_audio;
+use crate::deck::EnemyDeck;
+use crate::deck::PlayerDeck;
+use crate::level::Level;
+use crate::modifier::LevelModifiers;
+use crate::prelude::*;
+use crate::projectile::ProjectileConfig;
+use crate::rng::GameRng;
+use crate::screen::gameplay::GameplayAssets;
+use crate::ship::Weapon;
+
+pub(super) fn plugin(app: &mut App) {
+    app.configure::<EffectHandlers>();
+
+    app.add_effect_handler("projectile", spawn_projectile)
+        .add_effect_handler("heal", heal)
+        .add_effect_handler("shield", shield)
+        .add_effect_handler("cool", cool_reactor)
+        .add_effect_handler("draw", draw_modules)
+        .add_effect_handler("add_flux", add_flux)
+        .add_effect_handler("damage_over_time", damage_over_time)
+        .add_effect_handler("status", apply_status);
+}
+
+/// One effect of an action, performed in order with the action's other effects.
+#[derive(Reflect, Serialize, Deserialize, Clone, Debug)]
+pub enum ActionEffect {
+    /// Launch this projectile at the target.
+    Projectile(String),
+    /// Repair the source ship by this amount times flux.
+    Heal(f32),
+    /// Shield the source ship by this amount times flux.
+    Shield(f32),
+    /// Remove this much heat from every module in the source ship's reactor.
+    Cool(f32),
+    /// Draw this many modules from storage to hand.
+    Draw(usize),
+    /// Raise the source ship's flux by this amount.
+    AddFlux(f32),
+    /// Damage the target by `damage` times flux at the end of each round, for `rounds` rounds.
+    DamageOverTime { damage: f32, rounds: usize },
+    /// Apply a named status to the target for `rounds` rounds.
+    ///
+    /// See [`StatusEffects`] for the statuses that affect gameplay.
+    Status { name: String, rounds: usize },
+}
+
+impl ActionEffect {
+    /// The key of the handler for this effect in [`EffectHandlers`].
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Projectile(_) => "projectile",
+            Self::Heal(_) => "heal",
+            Self::Shield(_) => "shield",
+            Self::Cool(_) => "cool",
+            Self::Draw(_) => "draw",
+            Self::AddFlux(_) => "add_flux",
+            Self::DamageOverTime { .. } => "damage_over_time",
+            Self::Status { .. } => "status",
+        }
+    }
+
+    /// A line describing this effect for a module tooltip.
+    pub fn description(&self, projectile_config: &ProjectileConfig) -> Option<String> {
+        let round = |x: f32| (10.0 * x).round() / 10.0;
+        Some(match self {
+            Self::Projectile(key) => {
+                let projectile = projectile_config.projectiles.get(key)?;
+                format!("[b]Damage:[r] {} times flux", round(projectile.damage))
+            },
+            Self::Heal(x) => format!("[b]Heal:[r] {} times flux", round(*x)),
+            Self::Shield(x) => format!("[b]Shield:[r] {} times flux", round(*x)),
+            Self::Cool(x) => format!("[b]Cooling:[r] {} per reactor module", round(*x)),
+            Self::Draw(x) => format!("[b]Draw:[r] {x}"),
+            Self::AddFlux(x) => format!("[b]Flux:[r] {:+}", round(*x)),
+            Self::DamageOverTime { damage, rounds } => format!(
+                "[b]Damage over time:[r] {} times flux for {rounds} rounds",
+                round(*damage),
+            ),
+            Self::Status { name, rounds } => format!("[b]Status:[r] {name} for {rounds} rounds"),
+        })
+    }
+}
+
+/// The context an [`ActionEffect`] is performed in.
+#[derive(Clone, Debug)]
+pub struct EffectInput {
+    pub effect: ActionEffect,
+    pub source: Entity,
+    pub target: Entity,
+    /// The source ship's flux when the action was performed.
+    pub flux: f32,
+}
+
+/// One-shot systems that perform each kind of [`ActionEffect`].
+#[derive(Resource, Default, Debug)]
+pub struct EffectHandlers(HashMap<&'static str, SystemId<In<EffectInput>>>);
+
+impl Configure for EffectHandlers {
+    fn configure(app: &mut App) {
+        app.init_resource::<Self>();
+    }
+}
+
+impl EffectHandlers {
+    pub fn get(&self, kind: &str) -> Option<SystemId<In<EffectInput>>> {
+        self.0.get(kind).copied()
+    }
+}
+
+pub trait AppExtEffectHandler {
+    /// Register the system that performs effects of the given kind.
+    fn add_effect_handler<M>(
+        &mut self,
+        kind: &'static str,
+        handler: impl IntoSystem<In<EffectInput>, (), M> + 'static,
+    ) -> &mut Self;
+}
+
+impl AppExtEffectHandler for App {
+    fn add_effect_handler<M>(
+        &mut self,
+        kind: &'static str,
+        handler: impl IntoSystem<In<EffectInput>, (), M> + 'static,
+    ) -> &mut Self {
+        let id = self.register_system(handler);
+        self.world_mut()
+            .resource_mut::<EffectHandlers>()
+            .0
+            .insert(kind, id);
+        self
+    }
+}
+
+fn spawn_projectile(
+    In(input): In<EffectInput>,
+    mut commands: Commands,
+    projectile_config: ConfigRef<ProjectileConfig>,
+    audio_settings: Res<AudioSettings>,
+    ship_query: Query<(&Children, &Faction, &LinearVelocity)>,
+    children_query: Query<&Children>,
+    weapon_query: Query<&GlobalTransform, With<Weapon>>,
+    level_modifiers: Res<LevelModifiers>,
+    mut game_rng: ResMut<GameRng>,
+) {
+    let ActionEffect::Projectile(key) = &input.effect else {
+        return;
+    };
+    let projectile_config = r!(projectile_config.get());
+    let projectile = r!(projectile_config.projectiles.get(key));
+
+    // Choose a weapon on the ship.
+    let rng = &mut game_rng.combat;
+    let (children, &ship_faction, ship_velocity) = r!(ship_query.get(input.source));
+    let mut weapons = Vec::<&_>::new();
+    for &child in children {
+        weapons.extend(weapon_query.get(child));
+        for &child in children_query.get(child).into_iter().flatten() {
+            weapons.extend(weapon_query.get(child));
+        }
+    }
+    let weapon_gt = r!(weapons.choose(rng));
+    let weapon_transform = weapon_gt.compute_transform();
+
+    commands.spawn((
+        projectile.generate(
+            rng,
+            weapon_transform,
+            ship_velocity.0,
+            ship_faction,
+            input.target,
+            input.flux,
+            level_modifiers.projectile_speed(),
+        ),
+        DespawnOnExitState::<Level>::default(),
+    ));
+    if let Some(spawn_sfx) = &projectile.spawn_sfx {
+        commands.spawn((
+            sfx_audio(&audio_settings, spawn_sfx.clone(), 1.0),
+            DespawnOnExitState::<Level>::default(),
+        ));
+    }
+}
+
+fn heal(
+    In(input): In<EffectInput>,
+    mut commands: Commands,
+    game_assets: Res<GameplayAssets>,
+    audio_settings: Res<AudioSettings>,
+    mut game_rng: ResMut<GameRng>,
+) {
+    let ActionEffect::Heal(amount) = input.effect else {
+        return;
+    };
+    rq!(amount > f32::EPSILON);
+
+    commands
+        .entity(input.source)
+        .trigger(OnHeal(amount * input.flux));
+    commands.spawn((
+        sfx_audio(
+            &audio_settings,
+            game_assets.repair_sfx.clone(),
+            2f32.powf(1.0 / 12.0 * game_rng.cosmetic.gen_range(0..12) as f32),
+        ),
+        DespawnOnExitState::<Level>::default(),
+    ));
+}
+
+fn shield(In(input): In<EffectInput>, mut commands: Commands) {
+    let ActionEffect::Shield(amount) = input.effect else {
+        return;
+    };
+    rq!(amount > f32::EPSILON);
+
+    commands
+        .entity(input.source)
+        .trigger(OnShield(amount * input.flux));
+}
+
+fn cool_reactor(
+    In(input): In<EffectInput>,
+    faction_query: Query<&Faction>,
+    player_deck: ResMut<PlayerDeck>,
+    mut enemy_query: Query<&mut EnemyDeck>,
+) {
+    let ActionEffect::Cool(amount) = input.effect else {
+        return;
+    };
+
+    let deck = match r!(faction_query.get(input.source)) {
+        Faction::Player => player_deck.into_inner(),
+        Faction::Enemy => {
+            let enemy_deck = r!(enemy_query.get_mut(input.source)).into_inner();
+            // Only enemies with a reactor have modules to cool.
+            &mut rq!(enemy_deck.reactor.as_mut()).deck
+        },
+    };
+    for idx in 0..deck.reactor.len() {
+        deck.cool_slot(idx, amount);
+    }
+}
+
+fn draw_modules(
+    In(input): In<EffectInput>,
+    faction_query: Query<&Faction>,
+    player_deck: ResMut<PlayerDeck>,
+    mut enemy_query: Query<&mut EnemyDeck>,
+    game_rng: ResMut<GameRng>,
+) {
+    let ActionEffect::Draw(count) = input.effect else {
+        return;
+    };
+
+    let game_rng = game_rng.into_inner();
+    let (deck, rng) = match r!(faction_query.get(input.source)) {
+        Faction::Player => (player_deck.into_inner(), &mut game_rng.deck),
+        Faction::Enemy => {
+            let enemy_deck = r!(enemy_query.get_mut(input.source)).into_inner();
+            // Only enemies with a reactor have a hand to draw into.
+            (
+                &mut rq!(enemy_deck.reactor.as_mut()).deck,
+                &mut game_rng.enemy,
+            )
+        },
+    };
+    for _ in 0..count {
+        deck.draw_random(rng);
+    }
+}
+
+fn add_flux(
+    In(input): In<EffectInput>,
+    faction_query: Query<&Faction>,
+    mut player_deck: ResMut<PlayerDeck>,
+    mut enemy_query: Query<&mut EnemyDeck>,
+) {
+    let ActionEffect::AddFlux(amount) = input.effect else {
+        return;
+    };
+
+    let flux = match r!(faction_query.get(input.source)) {
+        Faction::Player => &mut player_deck.flux,
+        Faction::Enemy => &mut r!(enemy_query.get_mut(input.source)).into_inner().flux,
+    };
+    *flux = (*flux + amount).max(0.0);
+}
+
+fn damage_over_time(
+    In(input): In<EffectInput>,
+    mut commands: Commands,
+    mut dot_query: Query<&mut DamageOverTime>,
+) {
+    let ActionEffect::DamageOverTime { damage, rounds } = input.effect else {
+        return;
+    };
+    rq!(damage > f32::EPSILON && rounds > 0);
+
+    let tick = (damage * input.flux, rounds);
+    if let Ok(mut dot) = dot_query.get_mut(input.target) {
+        dot.0.push(tick);
+    } else {
+        commands
+            .entity(input.target)
+            .try_insert(DamageOverTime(vec![tick]));
+    }
+}
+
+fn apply_status(
+    In(input): In<EffectInput>,
+    mut commands: Commands,
+    mut status_query: Query<&mut StatusEffects>,
+) {
+    let ActionEffect::Status { name, rounds } = input.effect else {
+        return;
+    };
+    rq!(rounds > 0);
+
+    if let Ok(mut statuses) = status_query.get_mut(input.target) {
+        statuses.apply(name, rounds);
+    } else {
+        let mut statuses = StatusEffects::default();
+        statuses.apply(name, rounds);
+        commands.entity(input.target).try_insert(statuses);
+    }
+}
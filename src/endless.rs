@@ -0,0 +1,242 @@
+use bevy::asset::RenderAssetUsages;
+use bevy::render::render_resource::Extent3d;
+use bevy::render::render_resource::TextureDimension;
+use bevy::render::render_resource::TextureFormat;
+
+use crate::combat::shield::Shield;
+use crate::deck::EnemyDeck;
+use crate::level::EnemySetup;
+use crate::level::LevelSetup;
+use crate::modifier::LevelModifier;
+use crate::modifier::LevelModifiers;
+use crate::prelude::*;
+
+pub(super) fn plugin(app: &mut App) {
+    app.configure::<EndlessMode>();
+}
+
+/// Whether the current run continues past the authored levels with generated stars.
+#[derive(Resource, Reflect, Copy, Clone, Default, Debug)]
+#[reflect(Resource)]
+pub struct EndlessMode(pub bool);
+
+impl Configure for EndlessMode {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.init_resource::<Self>();
+    }
+}
+
+/// How to generate the stars of an endless run.
+#[derive(Reflect, Serialize, Deserialize, Debug)]
+#[serde(deny_unknown_fields, default)]
+pub struct EndlessConfig {
+    /// The difficulty budget of the first generated star, in enemy health.
+    pub base_budget: f32,
+    /// The factor the budget grows by with each star.
+    pub budget_growth: f32,
+    /// The fraction of each ship's budget spent on health.
+    pub health_share: f32,
+    /// The chance for each ship to carry a shield.
+    pub shield_chance: f64,
+    /// The budget cost of each point of volley rate past the first.
+    pub volley_rate_cost: f32,
+    pub max_volley_rate: usize,
+    pub max_volley_len: usize,
+    /// The actions a volley can be built from.
+    pub actions: Vec<EndlessAction>,
+    /// The chance for a star to have an environmental modifier.
+    pub modifier_chance: f64,
+    /// The modifiers to choose from. Their cost comes out of the enemy budget.
+    pub modifiers: Vec<EndlessModifier>,
+    /// The chance for a star to be guarded by two ships instead of one.
+    pub escort_chance: f64,
+    /// The chance for a star to have a second wave instead of one.
+    pub wave_chance: f64,
+    /// Greek letters and other designations to put in front of some star names.
+    pub name_prefixes: Vec<String>,
+    /// Syllables to build star names from.
+    pub name_syllables: Vec<String>,
+}
+
+impl Default for EndlessConfig {
+    fn default() -> Self {
+        Self {
+            base_budget: 500.0,
+            budget_growth: 1.1,
+            health_share: 0.7,
+            shield_chance: 0.0,
+            volley_rate_cost: 50.0,
+            max_volley_rate: 3,
+            max_volley_len: 20,
+            actions: vec![],
+            modifier_chance: 0.0,
+            modifiers: vec![],
+            escort_chance: 0.0,
+            wave_chance: 0.0,
+            name_prefixes: vec![],
+            name_syllables: vec![],
+        }
+    }
+}
+
+#[derive(Reflect, Serialize, Deserialize, Clone, Debug)]
+pub struct EndlessAction {
+    pub action: String,
+    /// The budget cost of adding this action to a volley.
+    pub cost: f32,
+}
+
+#[derive(Reflect, Serialize, Deserialize, Clone, Debug)]
+pub struct EndlessModifier {
+    pub modifier: LevelModifier,
+    /// The budget cost of the modifier.
+    pub cost: f32,
+}
+
+impl EndlessConfig {
+    /// Generate a star at this depth past the authored levels.
+    pub fn generate(&self, depth: usize, rng: &mut impl Rng) -> LevelSetup {
+        let mut budget = self.base_budget * self.budget_growth.powi(depth as _);
+
+        // Harsher environments come with weaker enemies.
+        let mut modifiers = vec![];
+        if rng.gen_bool(self.modifier_chance) {
+            if let Some(offer) = self.modifiers.choose(rng) {
+                if offer.cost < 0.5 * budget {
+                    modifiers.push(offer.modifier.clone());
+                    budget -= offer.cost;
+                }
+            }
+        }
+
+        // Split the budget between the ships.
+        let waves = if rng.gen_bool(self.wave_chance) {
+            vec![
+                vec![self.enemy_setup(0.35 * budget, vec2(59.0, 93.0), rng)],
+                vec![self.enemy_setup(0.65 * budget, vec2(59.0, 93.0), rng)],
+            ]
+        } else if rng.gen_bool(self.escort_chance) {
+            vec![vec![
+                self.enemy_setup(0.4 * budget, vec2(-41.0, 113.0), rng),
+                self.enemy_setup(0.6 * budget, vec2(159.0, 73.0), rng),
+            ]]
+        } else {
+            vec![vec![self.enemy_setup(budget, vec2(59.0, 93.0), rng)]]
+        };
+
+        LevelSetup {
+            name: self.star_name(rng),
+            waves,
+            victory: false,
+            modifiers: LevelModifiers(modifiers),
+        }
+    }
+
+    fn enemy_setup(&self, budget: f32, position: Vec2, rng: &mut impl Rng) -> EnemySetup {
+        let health = (self.health_share * budget).round().max(1.0);
+        let mut budget = budget - health;
+
+        // Shields cost as much as the health they absorb each round.
+        let mut shield = None;
+        if rng.gen_bool(self.shield_chance) {
+            let max = (0.1 * health).round();
+            shield = Some(Shield::new(max, (0.25 * max).round()));
+            budget -= max;
+        }
+
+        // Spend some of the rest on going deeper into the volley each round.
+        let affordable_rate = (0.3 * budget / self.volley_rate_cost).max(0.0) as usize;
+        let volley_rate =
+            1 + rng.gen_range(0..=affordable_rate.min(self.max_volley_rate.saturating_sub(1)));
+        budget -= (volley_rate - 1) as f32 * self.volley_rate_cost;
+
+        // Spend the rest on the volley itself.
+        let mut volley = vec![];
+        while volley.len() < self.max_volley_len {
+            let affordable = self
+                .actions
+                .iter()
+                .filter(|x| x.cost <= budget)
+                .collect::<Vec<_>>();
+            let Some(action) = affordable.choose(rng) else {
+                break;
+            };
+            volley.push(action.action.clone());
+            budget -= action.cost;
+        }
+
+        EnemySetup {
+            position,
+            deck: EnemyDeck {
+                max_health: health,
+                shield,
+                volley,
+                volley_rate,
+                ..default()
+            },
+            behavior: default(),
+        }
+    }
+
+    /// Generate a star name like "Tau Velorin" or "Kessara".
+    fn star_name(&self, rng: &mut impl Rng) -> String {
+        let syllable_count = rng.gen_range(2..=3);
+        let mut name = (0..syllable_count)
+            .filter_map(|_| self.name_syllables.choose(rng))
+            .map(String::as_str)
+            .collect::<String>();
+        if let Some(first) = name.get(..1) {
+            name = first.to_uppercase() + &name[1..];
+        }
+
+        match self.name_prefixes.choose(rng) {
+            Some(prefix) if rng.r#gen() => format!("{prefix} {name}"),
+            _ => name,
+        }
+    }
+}
+
+/// The size of generated backgrounds in pixels, before scaling up to fill the screen.
+const BACKGROUND_SIZE: UVec2 = UVec2::new(260, 155);
+
+/// Generate a starfield with nebula clouds for a star past the authored levels.
+pub fn noise_background(rng: &mut impl Rng) -> Image {
+    let mut cloud_noise = Noise::<common_noise::Perlin>::default();
+    cloud_noise.set_seed(rng.r#gen());
+    cloud_noise.set_period(80.0);
+    let mut detail_noise = Noise::<common_noise::Perlin>::default();
+    detail_noise.set_seed(rng.r#gen());
+    detail_noise.set_period(20.0);
+
+    let tint = LinearRgba::from(Color::hsl(rng.gen_range(0.0..360.0), 0.7, 0.4));
+    let accent = LinearRgba::from(Color::hsl(rng.gen_range(0.0..360.0), 0.6, 0.3));
+    let mut data = Vec::with_capacity(4 * (BACKGROUND_SIZE.x * BACKGROUND_SIZE.y) as usize);
+    for y in 0..BACKGROUND_SIZE.y {
+        for x in 0..BACKGROUND_SIZE.x {
+            let pos = vec2(x as f32, y as f32);
+            let cloud: f32 = cloud_noise.sample(pos);
+            let detail: f32 = detail_noise.sample(pos);
+            let density = (0.5 + 0.5 * cloud).powi(3);
+            let wisps = (density * (0.5 + 0.5 * detail)).clamp(0.0, 1.0);
+
+            let mut color = tint * density + accent * wisps;
+            if rng.gen_bool(0.004) {
+                color = LinearRgba::WHITE * rng.gen_range(0.4..1.0);
+            }
+            data.extend(Srgba::from(color.with_alpha(1.0)).to_u8_array());
+        }
+    }
+
+    Image::new(
+        Extent3d {
+            width: BACKGROUND_SIZE.x,
+            height: BACKGROUND_SIZE.y,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::RENDER_WORLD,
+    )
+}
@@ -0,0 +1,239 @@
+use crate::deck::PlayerDeck;
+use crate::endless::EndlessMode;
+use crate::level::Level;
+use crate::menu::Menu;
+use crate::menu::upgrade::UpgradeHistory;
+use crate::module::Module;
+use crate::module::ModuleStatus;
+use crate::prelude::*;
+use crate::replay::Playback;
+use crate::rng::GameRng;
+use crate::stats::Stats;
+use crate::util::storage;
+
+pub(super) fn plugin(app: &mut App) {
+    app.configure::<RunHistory>();
+}
+
+/// Every finished run, persisted across sessions.
+#[derive(Resource, Serialize, Deserialize, Default, Debug)]
+#[serde(default)]
+pub struct RunHistory {
+    pub version: u32,
+    /// Finished runs, from oldest to newest.
+    pub runs: Vec<RunRecord>,
+}
+
+impl Configure for RunHistory {
+    fn configure(app: &mut App) {
+        app.insert_resource(Self::load().unwrap_or_else(|| Self {
+            version: Self::VERSION,
+            ..default()
+        }));
+        app.add_systems(
+            StateFlush,
+            (
+                Menu::Victory.on_enter(record_victory),
+                Menu::Defeat.on_enter(record_defeat),
+            )
+                .run_if(not(resource_exists::<Playback>)),
+        );
+    }
+}
+
+impl RunHistory {
+    const FILE: &'static str = "history.ron";
+    /// Increment this when a change would break old history files.
+    const VERSION: u32 = 1;
+
+    fn load() -> Option<Self> {
+        let history = ron::from_str::<Self>(&storage::read(Self::FILE)?).ok()?;
+        if history.version != Self::VERSION {
+            warn!(
+                "Ignoring history file with version {} (expected {})",
+                history.version,
+                Self::VERSION,
+            );
+            return None;
+        }
+
+        Some(history)
+    }
+
+    fn store(&self) {
+        let text = r!(ron::to_string(self));
+        storage::write(Self::FILE, &text);
+    }
+
+    /// Add a finished run to the history.
+    ///
+    /// Retrying a star after defeat continues the same run, so it replaces that run's record.
+    fn add(&mut self, run: RunRecord) {
+        if self.runs.last().is_some_and(|x| x.run_id == run.run_id) {
+            self.runs.pop();
+        }
+        self.runs.push(run);
+        self.store();
+    }
+
+    pub fn victories(&self) -> usize {
+        self.runs.iter().filter(|x| x.victory).count()
+    }
+
+    pub fn highest_flux(&self) -> f32 {
+        self.runs
+            .iter()
+            .map(|x| x.stats.highest_flux)
+            .fold(0.0, f32::max)
+    }
+
+    pub fn highest_damage(&self) -> f32 {
+        self.runs
+            .iter()
+            .map(|x| x.stats.highest_damage)
+            .fold(0.0, f32::max)
+    }
+
+    /// The deepest star reached in an endless run, counting the star it ended at.
+    pub fn deepest_star(&self) -> usize {
+        self.runs
+            .iter()
+            .filter(|x| x.endless)
+            .map(|x| x.stars + 1)
+            .max()
+            .unwrap_or_default()
+    }
+
+    /// The duration of the fastest victory in seconds.
+    pub fn fastest_victory(&self) -> Option<f32> {
+        self.runs
+            .iter()
+            .filter(|x| x.victory)
+            .map(|x| x.stats.duration)
+            .min_by(f32::total_cmp)
+    }
+
+    /// The stats of every run added together.
+    pub fn totals(&self) -> Stats {
+        let mut totals = Stats::default();
+        for run in &self.runs {
+            for (action, count) in &run.stats.actions {
+                *totals.actions.entry(action.clone()).or_default() += count;
+            }
+            totals.highest_flux = totals.highest_flux.max(run.stats.highest_flux);
+            totals.damage_given += run.stats.damage_given;
+            totals.damage_taken += run.stats.damage_taken;
+            totals.highest_damage = totals.highest_damage.max(run.stats.highest_damage);
+            totals.total_rounds += run.stats.total_rounds;
+            totals.duration += run.stats.duration;
+        }
+        totals
+    }
+}
+
+/// A summary of a finished run.
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+#[serde(default)]
+pub struct RunRecord {
+    /// The id of the run, shared by the records of its retries.
+    pub run_id: u64,
+    pub seed: u64,
+    pub victory: bool,
+    /// Whether the run continued past the authored levels.
+    pub endless: bool,
+    /// The number of stars defended.
+    pub stars: usize,
+    /// The name of the player's ship.
+    pub ship: String,
+    pub max_health: f32,
+    pub heat_capacity: f32,
+    pub reactor_slots: usize,
+    /// Every module the player owned at the end of the run.
+    pub modules: Vec<Module>,
+    pub upgrades: UpgradeHistory,
+    pub stats: Stats,
+}
+
+impl RunRecord {
+    fn new(
+        victory: bool,
+        endless: bool,
+        stars: usize,
+        game_rng: &GameRng,
+        player_deck: &PlayerDeck,
+        upgrade_history: &UpgradeHistory,
+        stats: &Stats,
+    ) -> Self {
+        let modules = player_deck
+            .storage
+            .iter()
+            .chain(&player_deck.hand)
+            .chain(&player_deck.reactor)
+            .filter(|x| !matches!(x.status, ModuleStatus::SlotEmpty))
+            .map(|x| Module::new(&x.condition, &x.effect))
+            .collect();
+
+        Self {
+            run_id: game_rng.run_id,
+            seed: game_rng.seed,
+            victory,
+            endless,
+            stars,
+            ship: player_deck.name.clone(),
+            max_health: player_deck.max_health,
+            heat_capacity: player_deck.heat_capacity,
+            reactor_slots: player_deck.reactor.len(),
+            modules,
+            upgrades: upgrade_history.clone(),
+            stats: stats.clone(),
+        }
+    }
+}
+
+fn record_victory(
+    level: NextRef<Level>,
+    game_rng: Res<GameRng>,
+    endless: Res<EndlessMode>,
+    player_deck: Res<PlayerDeck>,
+    upgrade_history: Res<UpgradeHistory>,
+    stats: Res<Stats>,
+    mut history: ResMut<RunHistory>,
+) {
+    let stars = r!(level.get()).0 + 1;
+    history.add(RunRecord::new(
+        true,
+        endless.0,
+        stars,
+        &game_rng,
+        &player_deck,
+        &upgrade_history,
+        &stats,
+    ));
+}
+
+fn record_defeat(
+    level: NextRef<Level>,
+    game_rng: Res<GameRng>,
+    endless: Res<EndlessMode>,
+    player_deck: Res<PlayerDeck>,
+    upgrade_history: Res<UpgradeHistory>,
+    stats: Res<Stats>,
+    mut history: ResMut<RunHistory>,
+) {
+    let stars = r!(level.get()).0;
+    history.add(RunRecord::new(
+        false,
+        endless.0,
+        stars,
+        &game_rng,
+        &player_deck,
+        &upgrade_history,
+        &stats,
+    ));
+}
+
+/// Format a duration in seconds as minutes and seconds.
+pub fn format_duration(seconds: f32) -> String {
+    let seconds = seconds as u64;
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
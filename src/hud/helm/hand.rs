@@ -1,3 +1,4 @@
+use crate::animation::offset::NodeOffset;
 use crate::animation::shake::NodeShake;
 use crate::core::audio::AudioSettings;
 use crate::core::audio::sfx_audio;
@@ -5,6 +6,7 @@ use crate::deck::PlayerDeck;
 use crate::hud::module::module;
 use crate::level::Level;
 use crate::module::ModuleConfig;
+use crate::phase::Phase;
 use crate::phase::helm::HelmActions;
 use crate::prelude::*;
 use crate::screen::gameplay::GameplayAssets;
@@ -69,6 +71,7 @@ fn sync_hand_display(
                         module(&game_assets, module_config, item, player_deck.heat_capacity),
                         Pickable::IGNORE,
                         NodeShake::default(),
+                        NodeOffset::default(),
                         Patch(move |entity| {
                             if i == selected_idx {
                                 r!(entity.get_mut::<Node>()).top = Vw(-2.0);
@@ -93,6 +96,8 @@ impl Configure for HandIndex {
         );
         app.add_observer(select_module_on_hover);
         app.add_observer(play_or_discard_module_on_click);
+        app.add_observer(drag_module_on_drag);
+        app.add_observer(drop_module_on_drag_end);
     }
 }
 
@@ -144,3 +149,34 @@ fn play_or_discard_module_on_click(
         PointerButton::Middle => {},
     }
 }
+
+fn drag_module_on_drag(
+    trigger: Trigger<Pointer<Drag>>,
+    phase: CurrentRef<Phase>,
+    container_query: Query<&Children, With<HandIndex>>,
+    mut offset_query: Query<&mut NodeOffset>,
+) {
+    rq!(matches!(trigger.event.button, PointerButton::Primary));
+    rq!(phase.is_in(&Phase::Helm));
+    let target = rq!(trigger.get_target());
+    let children = rq!(container_query.get(target));
+
+    // The module follows the pointer until it's dropped onto a reactor slot.
+    let distance = trigger.event.distance;
+    for &child in children {
+        let mut offset = cq!(offset_query.get_mut(child));
+        *offset = NodeOffset::new(Px(distance.x), Px(distance.y));
+    }
+}
+
+fn drop_module_on_drag_end(
+    trigger: Trigger<Pointer<DragEnd>>,
+    container_query: Query<&Children, With<HandIndex>>,
+    mut offset_query: Query<&mut NodeOffset>,
+) {
+    let target = rq!(trigger.get_target());
+    let children = rq!(container_query.get(target));
+    for &child in children {
+        *cq!(offset_query.get_mut(child)) = NodeOffset::default();
+    }
+}
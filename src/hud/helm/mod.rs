@@ -107,10 +107,10 @@ fn open_help_menu(
 
 fn toggle_reactor_overlay(
     trigger: Trigger<Pointer<Click>>,
-    mut _gameplay_action: ResMut<ActionState<GameplayAction>>,
+    mut gameplay_action: ResMut<ActionState<GameplayAction>>,
 ) {
     rq!(matches!(trigger.event.button, PointerButton::Primary));
-    // TODO: gameplay_action.press(&GameplayAction::ToggleOverlay);
+    gameplay_action.press(&GameplayAction::ToggleOverlay);
 }
 
 fn open_pause_menu(
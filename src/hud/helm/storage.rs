@@ -2,6 +2,7 @@ use crate::animation::shake::NodeShake;
 use crate::deck::PlayerDeck;
 use crate::hud::HudConfig;
 use crate::prelude::*;
+use crate::screen::gameplay::GameplayAction;
 use crate::screen::gameplay::GameplayAssets;
 
 pub(super) fn plugin(app: &mut App) {
@@ -21,6 +22,9 @@ pub(super) fn storage_display(hud_config: &HudConfig, game_assets: &GameplayAsse
         },
         hud_config.module_shake,
         Tooltip::fixed(Anchor::BottomLeft, ""),
+        Patch(|entity| {
+            entity.observe(open_deck_menu_on_click);
+        }),
         children![(
             widget::small_colored_label(ThemeColor::IconText, ""),
             StorageLabel,
@@ -74,7 +78,7 @@ fn sync_storage_display_tooltip(
         }
 
         tooltip.content = TooltipContent::Primary(RichText::from_sections(parse_rich(format!(
-            "[b]Storage[r]\n\nThere {} {} reactor module{} remaining to draw:\n\n{}",
+            "[b]Storage (Tab)[r]\n\nThere {} {} reactor module{} remaining to draw:\n\n{}\n\nClick to view your deck.",
             are(total),
             total,
             plural(total),
@@ -83,6 +87,14 @@ fn sync_storage_display_tooltip(
     }
 }
 
+fn open_deck_menu_on_click(
+    trigger: Trigger<Pointer<Click>>,
+    mut gameplay_action: ResMut<ActionState<GameplayAction>>,
+) {
+    rq!(matches!(trigger.event.button, PointerButton::Primary));
+    gameplay_action.press(&GameplayAction::ToggleDeck);
+}
+
 fn plural(num: usize) -> &'static str {
     if num == 1 { "" } else { "s" }
 }
@@ -0,0 +1,213 @@
+use crate::boss::EnemyBehavior;
+use crate::combat::death::Dead;
+use crate::combat::health::Health;
+use crate::combat::target::EnemyIndex;
+use crate::deck::EnemyDeck;
+use crate::module::ModuleConfig;
+use crate::phase::Phase;
+use crate::phase::Round;
+use crate::prelude::*;
+use crate::projectile::ProjectileConfig;
+use crate::rng::GameRng;
+use crate::ship::EnemyShip;
+
+pub(super) fn plugin(app: &mut App) {
+    app.configure::<(EnemyIntent, EnemyIntentPanel)>();
+}
+
+pub(super) fn enemy_intent_panel() -> impl Bundle {
+    (
+        Name::new("EnemyIntentPanel"),
+        EnemyIntentPanel,
+        Node {
+            display: Display::None,
+            width: Vw(20.0),
+            padding: UiRect::all(Vw(0.8)),
+            border: UiRect::all(Vw(0.2083)),
+            row_gap: Vw(0.5),
+            ..Node::COLUMN
+        },
+        ThemeColor::Monitor.set::<BackgroundColor>(),
+        ThemeColor::MonitorDimText.set::<BorderColor>(),
+        Tooltip::fixed(
+            Anchor::CenterLeft,
+            parse_rich(
+                "[b]Enemy intent[r]\n\nThe actions each enemy will fire this round and their flux.\nDamage is estimated before shields and armor.",
+            ),
+        ),
+    )
+}
+
+/// The attacks the enemy ships are predicted to make this round in turn order,
+/// or `None` outside of the helm phase.
+#[derive(Resource, Reflect, Default, Debug)]
+#[reflect(Resource)]
+pub struct EnemyIntent(pub Option<Vec<IntendedAttack>>);
+
+#[derive(Reflect, Debug)]
+pub struct IntendedAttack {
+    /// The name of the enemy ship.
+    pub name: String,
+    /// The actions the ship will fire and the flux of each.
+    pub actions: Vec<(String, f32)>,
+}
+
+impl Configure for EnemyIntent {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.init_resource::<Self>();
+        app.add_systems(StateFlush, Phase::Helm.on_exit(clear_enemy_intent));
+        app.add_systems(
+            Update,
+            Phase::Helm.on_update(
+                update_enemy_intent.in_set(UpdateSystems::SyncEarly).run_if(
+                    (|intent: Res<EnemyIntent>| intent.0.is_none())
+                        .or(any_match_filter::<Added<EnemyShip>>),
+                ),
+            ),
+        );
+    }
+}
+
+fn update_enemy_intent(
+    module_config: ConfigRef<ModuleConfig>,
+    round: Res<Round>,
+    game_rng: Res<GameRng>,
+    enemy_query: Query<
+        (&EnemyIndex, &EnemyDeck, &EnemyBehavior, &Health),
+        (With<EnemyShip>, Without<Dead>),
+    >,
+    mut intent: ResMut<EnemyIntent>,
+) {
+    let module_config = r!(module_config.get());
+
+    // Predict reactor draws from a copy of this round's enemy stream.
+    let mut rng = game_rng.enemy.clone();
+    let mut enemies = enemy_query.iter().collect::<Vec<_>>();
+    enemies.sort_by_key(|(idx, ..)| **idx);
+    intent.0 = Some(
+        enemies
+            .into_iter()
+            .map(|(idx, enemy_deck, behavior, health)| {
+                // Account for a boss phase that will begin before the attack.
+                let mut enemy_deck = enemy_deck.clone();
+                let mut behavior = behavior.clone();
+                while behavior
+                    .update_phase(&mut enemy_deck, health.current)
+                    .is_some()
+                {}
+                let enrage = behavior.enrage_flux(round.0);
+
+                IntendedAttack {
+                    name: enemy_deck.display_name(idx.0),
+                    actions: enemy_deck
+                        .intent(round.0, module_config, &mut rng)
+                        .into_iter()
+                        .map(|(action, flux)| (action, flux + enrage))
+                        .collect(),
+                }
+            })
+            .collect(),
+    );
+}
+
+fn clear_enemy_intent(mut intent: ResMut<EnemyIntent>) {
+    intent.0 = None;
+}
+
+/// Lists the [`EnemyIntent`] with an estimate of the total damage.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct EnemyIntentPanel;
+
+impl Configure for EnemyIntentPanel {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            Update,
+            sync_enemy_intent_panel
+                .in_set(UpdateSystems::SyncLate)
+                .run_if(resource_changed::<EnemyIntent>.or(any_match_filter::<Added<Self>>)),
+        );
+    }
+}
+
+fn sync_enemy_intent_panel(
+    mut commands: Commands,
+    module_config: ConfigRef<ModuleConfig>,
+    projectile_config: ConfigRef<ProjectileConfig>,
+    intent: Res<EnemyIntent>,
+    mut panel_query: Query<(Entity, &mut Node), With<EnemyIntentPanel>>,
+) {
+    let module_config = r!(module_config.get());
+    let projectile_config = r!(projectile_config.get());
+    let attacks = intent.0.as_deref().unwrap_or_default();
+    let damage = attacks
+        .iter()
+        .flat_map(|x| &x.actions)
+        .filter_map(|(action, flux)| {
+            let action = module_config.actions.get(action)?;
+            Some(projectile_config.action_damage(action, *flux))
+        })
+        .sum::<f32>();
+
+    for (entity, mut node) in &mut panel_query {
+        node.display = if attacks.iter().all(|x| x.actions.is_empty()) {
+            Display::None
+        } else {
+            Display::Flex
+        };
+
+        commands
+            .entity(entity)
+            .despawn_related::<Children>()
+            .with_children(|parent| {
+                parent.spawn(widget::small_colored_label(
+                    ThemeColor::MonitorText,
+                    "[b]Enemy intent",
+                ));
+                for attack in attacks {
+                    cq!(!attack.actions.is_empty());
+                    if attacks.len() > 1 {
+                        parent.spawn(widget::tiny_label(&attack.name));
+                    }
+                    parent
+                        .spawn((
+                            Name::new("IntentActions"),
+                            Node {
+                                flex_wrap: FlexWrap::Wrap,
+                                column_gap: Vw(0.4),
+                                row_gap: Vw(0.4),
+                                ..Node::ROW
+                            },
+                        ))
+                        .with_children(|parent| {
+                            for (action, flux) in &attack.actions {
+                                let info = cq!(module_config.actions.get(action));
+                                parent.spawn((
+                                    Name::new("IntentAction"),
+                                    ImageNode::from(info.effect_icon.clone()),
+                                    Node {
+                                        width: Vw(3.0),
+                                        aspect_ratio: Some(1.0),
+                                        ..default()
+                                    },
+                                    children![(
+                                        widget::tiny_label(format!("[b]{flux}x")),
+                                        Node {
+                                            right: Val::ZERO,
+                                            bottom: Val::ZERO,
+                                            ..Node::DEFAULT.abs()
+                                        },
+                                    )],
+                                ));
+                            }
+                        });
+                }
+                parent.spawn(widget::small_colored_label(
+                    ThemeColor::MonitorDimText,
+                    format!("~{} damage", damage.round()),
+                ));
+            });
+    }
+}
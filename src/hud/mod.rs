@@ -1,6 +1,9 @@
 pub mod helm;
+pub mod intent;
 pub mod module;
 pub mod reactor;
+pub mod relic;
+pub mod target;
 
 use crate::animation::shake::NodeShake;
 use crate::animation::shake::Shake;
@@ -15,7 +18,14 @@ use crate::util::math::ExponentialFit;
 pub(super) fn plugin(app: &mut App) {
     app.configure::<(ConfigHandle<HudConfig>, Hud)>();
 
-    app.add_plugins((helm::plugin, module::plugin, reactor::plugin));
+    app.add_plugins((
+        helm::plugin,
+        intent::plugin,
+        module::plugin,
+        reactor::plugin,
+        relic::plugin,
+        target::plugin,
+    ));
 }
 
 pub fn hud(hud_config: &HudConfig, game_assets: &GameplayAssets) -> impl Bundle {
@@ -30,7 +40,21 @@ pub fn hud(hud_config: &HudConfig, game_assets: &GameplayAssets) -> impl Bundle
             (
                 Name::new("Column"),
                 Node::COLUMN.reverse().grow(),
-                children![helm::helm(hud_config, game_assets)],
+                children![
+                    helm::helm(hud_config, game_assets),
+                    relic::relic_bar(),
+                    (
+                        Name::new("EnemyColumn"),
+                        Node {
+                            top: Vw(4.0),
+                            right: Vw(1.0),
+                            row_gap: Vw(0.5),
+                            align_items: AlignItems::End,
+                            ..Node::COLUMN.abs()
+                        },
+                        children![target::target_bar(), intent::enemy_intent_panel()],
+                    ),
+                ],
             )
         ],
     )
@@ -46,6 +70,10 @@ pub struct HudConfig {
 
     module_shake: NodeShake,
     module_flux_trauma: ExponentialFit,
+    forecast_overheat_color: Color,
+    slot_cursor_color: Color,
+    slot_held_color: Color,
+    slot_neighbor_color: Color,
 
     pub camera_shake: Shake,
     pub camera_shake_rotation: ShakeRotation,
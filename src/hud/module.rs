@@ -1,3 +1,4 @@
+use crate::module::Condition;
 use crate::module::Module;
 use crate::module::ModuleConfig;
 use crate::module::ModuleStatus;
@@ -24,7 +25,6 @@ pub fn module(
     }
     .clone();
 
-    let condition = module_config.action(&module.condition);
     let effect = module_config.action(&module.effect);
     let show_icons = if matches!(
         module.status,
@@ -34,6 +34,14 @@ pub fn module(
     } else {
         1.0
     };
+    let mut condition_layers = vec![];
+    push_condition_layers(
+        &mut condition_layers,
+        module_config,
+        &Condition::parse(&module.condition),
+        Vec2::ZERO,
+        1.0,
+    );
 
     let heat = match module.status {
         ModuleStatus::SlotOverheated => 1.0,
@@ -58,10 +66,34 @@ pub fn module(
         children![
             (
                 Name::new("Condition"),
-                ImageNode::from(condition.condition_icon.clone())
-                    .with_color(Color::WHITE.with_alpha(show_icons)),
                 Node::default().full_size().abs(),
                 Pickable::IGNORE,
+                Children::spawn(SpawnWith(move |parent: &mut ChildSpawner| {
+                    for layer in condition_layers {
+                        let node = |offset: Vec2| Node {
+                            left: Percent(100.0 * offset.x),
+                            top: Percent(100.0 * offset.y),
+                            ..Node::default().abs()
+                        };
+                        parent.spawn((
+                            ImageNode::from(layer.image)
+                                .with_color(Color::WHITE.with_alpha(show_icons)),
+                            Node {
+                                width: Percent(100.0 * layer.scale),
+                                height: Percent(100.0 * layer.scale),
+                                ..node(layer.offset)
+                            },
+                            Pickable::IGNORE,
+                        ));
+                        cq!(show_icons > 0.0);
+                        let label = cq!(layer.label);
+                        parent.spawn((
+                            widget::tiny_label(format!("[b]{label}")),
+                            node(layer.offset + layer.scale * vec2(0.53, 0.12)),
+                            Pickable::IGNORE,
+                        ));
+                    }
+                })),
             ),
             (
                 Name::new("Effect"),
@@ -82,6 +114,57 @@ pub fn module(
     )
 }
 
+/// One layer of a condition icon, positioned and sized as a fraction of the module.
+struct ConditionLayer {
+    image: Handle<Image>,
+    offset: Vec2,
+    scale: f32,
+    label: Option<String>,
+}
+
+fn push_condition_layers(
+    layers: &mut Vec<ConditionLayer>,
+    module_config: &ModuleConfig,
+    condition: &Condition,
+    offset: Vec2,
+    scale: f32,
+) {
+    let icons = &module_config.condition_icons;
+    let mut push = |image: &Handle<Image>, label: Option<String>| {
+        layers.push(ConditionLayer {
+            image: image.clone(),
+            offset,
+            scale,
+            label,
+        })
+    };
+
+    match condition {
+        Condition::Start => push(&module_config.action("").condition_icon, None),
+        Condition::Action(action) => push(&module_config.action(action).condition_icon, None),
+        Condition::Any => push(&icons.any, None),
+        Condition::Not(x) => {
+            push_condition_layers(layers, module_config, x, offset, scale);
+            layers.push(ConditionLayer {
+                image: icons.not.clone(),
+                offset,
+                scale,
+                label: None,
+            });
+        },
+        Condition::OneOf(xs) => {
+            // Shrink the options and spread them along the diagonal of the condition area.
+            let step = 0.22 / xs.len().saturating_sub(1).max(1) as f32;
+            for (i, x) in xs.iter().enumerate() {
+                let offset = offset + Vec2::splat(scale * (0.035 + step * i as f32));
+                push_condition_layers(layers, module_config, x, offset, 0.5 * scale);
+            }
+        },
+        Condition::ChainAtLeast(n) => push(&icons.chain, Some(n.to_string())),
+        Condition::FluxAtLeast(n) => push(&icons.flux, Some(n.to_string())),
+    }
+}
+
 #[derive(Component, Reflect, Debug)]
 #[reflect(Component)]
 struct ModuleSlotGlow;
@@ -1,11 +1,12 @@
 use crate::animation::shake::NodeShake;
 use crate::deck::PlayerDeck;
 use crate::hud::HudConfig;
+use crate::hud::reactor::forecast::Forecast;
 use crate::phase::Phase;
 use crate::prelude::*;
 
 pub(super) fn plugin(app: &mut App) {
-    app.configure::<FluxLabel>();
+    app.configure::<(FluxLabel, PredictedFluxLabel)>();
 }
 
 pub(super) fn flux_display(hud_config: &HudConfig) -> impl Bundle {
@@ -15,6 +16,7 @@ pub(super) fn flux_display(hud_config: &HudConfig) -> impl Bundle {
             width: Vw(22.5),
             height: Vw(5.0),
             border: UiRect::all(Vw(0.2083)),
+            column_gap: Vw(1.0),
             ..Node::ROW.center()
         },
         ThemeColor::Monitor.set::<BackgroundColor>(),
@@ -25,11 +27,17 @@ pub(super) fn flux_display(hud_config: &HudConfig) -> impl Bundle {
                 "[b]Flux multiplier[r]\n\nChain \"reactor modules\" together to multiply their output.",
             ),
         ),
-        children![(
-            FluxLabel,
-            widget::colored_label(default(), ""),
-            hud_config.flux_label_shake,
-        )],
+        children![
+            (
+                FluxLabel,
+                widget::colored_label(default(), ""),
+                hud_config.flux_label_shake,
+            ),
+            (
+                PredictedFluxLabel,
+                widget::small_colored_label(ThemeColor::MonitorDimText, ""),
+            ),
+        ],
     )
 }
 
@@ -90,3 +98,41 @@ fn sync_flux_label(
         *text = new_text;
     }
 }
+
+/// Shows the flux predicted by the reactor [`Forecast`].
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct PredictedFluxLabel;
+
+impl Configure for PredictedFluxLabel {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            Update,
+            sync_predicted_flux_label
+                .in_set(UpdateSystems::SyncLate)
+                .run_if(
+                    resource_changed::<Forecast>.or(any_match_filter::<Added<PredictedFluxLabel>>),
+                ),
+        );
+    }
+}
+
+fn sync_predicted_flux_label(
+    forecast: Res<Forecast>,
+    mut label_query: Query<&mut RichText, With<PredictedFluxLabel>>,
+) {
+    let text = forecast
+        .shown()
+        .map(|x| {
+            if x.meltdown > 0.0 {
+                format!("predicted {}x, {} meltdown", x.flux, x.meltdown)
+            } else {
+                format!("predicted {}x", x.flux)
+            }
+        })
+        .unwrap_or_default();
+    for mut label in &mut label_query {
+        label.sections = parse_rich(&text);
+    }
+}
@@ -0,0 +1,123 @@
+use crate::deck::PlayerDeck;
+use crate::deck::ReactorForecast;
+use crate::hud::HudConfig;
+use crate::module::ModuleConfig;
+use crate::phase::Phase;
+use crate::prelude::*;
+use crate::screen::Screen;
+use crate::screen::gameplay::GameplayAction;
+
+pub(super) fn plugin(app: &mut App) {
+    app.configure::<Forecast>();
+}
+
+/// The reactor forecast shown during the helm phase.
+#[derive(Resource, Reflect, Debug)]
+#[reflect(Resource)]
+pub struct Forecast {
+    /// Whether to draw the forecast over the reactor.
+    pub visible: bool,
+    /// The forecast for the current hand and reactor, or `None` outside of the helm phase.
+    pub prediction: Option<ReactorForecast>,
+}
+
+impl Configure for Forecast {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.insert_resource(Self {
+            visible: true,
+            prediction: None,
+        });
+        app.add_systems(
+            StateFlush,
+            Phase::Helm.on_edge(clear_forecast, update_forecast),
+        );
+        app.add_systems(
+            Update,
+            (
+                Phase::Helm.on_update(
+                    update_forecast
+                        .in_set(UpdateSystems::SyncLate)
+                        .run_if(resource_changed::<PlayerDeck>),
+                ),
+                Screen::Gameplay.on_update(
+                    toggle_forecast
+                        .in_set(UpdateSystems::RecordInput)
+                        .run_if(action_just_pressed(GameplayAction::ToggleOverlay)),
+                ),
+            ),
+        );
+    }
+}
+
+impl Forecast {
+    /// The prediction, if it should be shown.
+    pub fn shown(&self) -> Option<&ReactorForecast> {
+        self.prediction.as_ref().filter(|_| self.visible)
+    }
+}
+
+fn update_forecast(
+    module_config: ConfigRef<ModuleConfig>,
+    player_deck: Res<PlayerDeck>,
+    mut forecast: ResMut<Forecast>,
+) {
+    let module_config = r!(module_config.get());
+    let prediction = Some(player_deck.forecast(module_config));
+    rq!(forecast.prediction != prediction);
+    forecast.prediction = prediction;
+}
+
+fn clear_forecast(mut forecast: ResMut<Forecast>) {
+    forecast.prediction = None;
+}
+
+fn toggle_forecast(mut forecast: ResMut<Forecast>) {
+    forecast.visible ^= true;
+}
+
+/// An overlay for a reactor slot showing its predicted activation order and heat.
+pub fn forecast_overlay(
+    hud_config: &HudConfig,
+    prediction: &ReactorForecast,
+    idx: usize,
+    heat_capacity: f32,
+) -> Option<impl Bundle> {
+    let order = prediction.order.iter().position(|&x| x == idx)?;
+    let heat = prediction.heat.get(idx).copied().unwrap_or_default();
+    let overheated = prediction.overheated.get(idx).copied().unwrap_or_default();
+
+    Some((
+        Name::new("ForecastOverlay"),
+        Node {
+            padding: UiRect::all(Vw(0.4)),
+            justify_content: JustifyContent::SpaceBetween,
+            ..Node::COLUMN.full_size().abs()
+        },
+        BackgroundColor(if overheated {
+            hud_config.forecast_overheat_color
+        } else {
+            Color::NONE
+        }),
+        ZIndex(2),
+        Pickable::IGNORE,
+        children![
+            (
+                widget::tiny_label(format!("[b]{}", order + 1)),
+                Node {
+                    align_self: AlignSelf::Start,
+                    ..default()
+                },
+                Pickable::IGNORE,
+            ),
+            (
+                widget::tiny_label(format!("{}/{heat_capacity}", (10.0 * heat).round() / 10.0)),
+                Node {
+                    align_self: AlignSelf::End,
+                    ..default()
+                },
+                Pickable::IGNORE,
+            ),
+        ],
+    ))
+}
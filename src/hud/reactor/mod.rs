@@ -1,22 +1,33 @@
 pub mod flux_display;
+pub mod forecast;
 
 use crate::core::audio::AudioSettings;
 use crate::core::audio::sfx_audio;
 use crate::deck::PlayerDeck;
 use crate::hud::HudConfig;
+use crate::hud::helm::hand::HandIndex;
 use crate::hud::module::module;
+use crate::hud::reactor::forecast::Forecast;
+use crate::hud::reactor::forecast::forecast_overlay;
 use crate::level::Level;
 use crate::module::ModuleConfig;
 use crate::module::ModuleStatus;
 use crate::phase::Phase;
+use crate::phase::helm::HelmHistory;
 use crate::prelude::*;
 use crate::projectile::ProjectileConfig;
 use crate::screen::gameplay::GameplayAssets;
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_plugins(flux_display::plugin);
+    app.add_plugins((flux_display::plugin, forecast::plugin));
 
-    app.configure::<(ReactorGrid, ReactorIndex)>();
+    app.configure::<(
+        ReactorGrid,
+        ReactorIndex,
+        RemoveReactorModule,
+        InsertReactorModule,
+        SwapReactorModules,
+    )>();
 }
 
 pub(super) fn reactor(hud_config: &HudConfig, game_assets: &GameplayAssets) -> impl Bundle {
@@ -40,7 +51,6 @@ fn reactor_grid() -> impl Bundle {
             display: Display::Grid,
             row_gap: Vw(1.25),
             column_gap: Vw(1.25),
-            grid_template_columns: RepeatedGridTrack::flex(3, 1.0),
             ..Node::default().full_width()
         },
         ReactorGrid,
@@ -56,9 +66,11 @@ impl Configure for ReactorGrid {
         app.register_type::<Self>();
         app.add_systems(
             Update,
-            sync_reactor_grid
-                .in_set(UpdateSystems::SyncLate)
-                .run_if(resource_changed::<PlayerDeck>.or(any_match_filter::<Added<Self>>)),
+            sync_reactor_grid.in_set(UpdateSystems::SyncLate).run_if(
+                resource_changed::<PlayerDeck>
+                    .or(resource_changed::<Forecast>)
+                    .or(any_match_filter::<Added<Self>>),
+            ),
         );
     }
 }
@@ -70,12 +82,15 @@ fn sync_reactor_grid(
     module_config: ConfigRef<ModuleConfig>,
     projectile_config: ConfigRef<ProjectileConfig>,
     player_deck: Res<PlayerDeck>,
-    grid_query: Query<Entity, With<ReactorGrid>>,
+    forecast: Res<Forecast>,
+    mut grid_query: Query<(Entity, &mut Node), With<ReactorGrid>>,
 ) {
     let hud_config = r!(hud_config.get());
     let module_config = r!(module_config.get());
     let projectile_config = r!(projectile_config.get());
-    for entity in &grid_query {
+    for (entity, mut node) in &mut grid_query {
+        node.grid_template_columns =
+            RepeatedGridTrack::flex(module_config.reactor_columns() as _, 1.0);
         commands
             .entity(entity)
             .despawn_related::<Children>()
@@ -90,9 +105,10 @@ fn sync_reactor_grid(
                         }
                     }
 
-                    parent.spawn((
+                    let mut entity = parent.spawn((
                         ReactorIndex(i),
                         module(&game_assets, module_config, slot, player_deck.heat_capacity),
+                        Outline::new(Vw(0.3), Val::ZERO, Color::NONE),
                         shake,
                         Tooltip::fixed(
                             Anchor::CenterRight,
@@ -104,8 +120,14 @@ fn sync_reactor_grid(
                         ),
                         Patch(|entity| {
                             entity.observe(play_hover_sfx_on_hover);
+                            entity.observe(select_slot_on_hover);
                         }),
                     ));
+                    if let Some(overlay) = forecast.shown().and_then(|prediction| {
+                        forecast_overlay(hud_config, prediction, i, player_deck.heat_capacity)
+                    }) {
+                        entity.with_child(overlay);
+                    }
                 }
             });
     }
@@ -133,6 +155,16 @@ fn play_hover_sfx_on_hover(
     ));
 }
 
+fn select_slot_on_hover(
+    trigger: Trigger<Pointer<Over>>,
+    reactor_module_query: Query<&ReactorIndex>,
+    mut player_deck: ResMut<PlayerDeck>,
+) {
+    let target = rq!(trigger.get_target());
+    let idx = rq!(reactor_module_query.get(target));
+    player_deck.bypass_change_detection().slot_idx = idx.0;
+}
+
 #[derive(Component, Reflect)]
 #[reflect(Component)]
 pub struct ReactorIndex(pub usize);
@@ -140,29 +172,179 @@ pub struct ReactorIndex(pub usize);
 impl Configure for ReactorIndex {
     fn configure(app: &mut App) {
         app.register_type::<Self>();
+        app.add_systems(
+            Update,
+            apply_outline_to_selected_slot.in_set(UpdateSystems::SyncLate),
+        );
         app.add_observer(discard_module_on_right_click);
+        app.add_observer(insert_or_swap_module_on_drop);
+    }
+}
+
+fn apply_outline_to_selected_slot(
+    hud_config: ConfigRef<HudConfig>,
+    module_config: ConfigRef<ModuleConfig>,
+    phase: CurrentRef<Phase>,
+    player_deck: Res<PlayerDeck>,
+    mut slot_query: Query<(&ReactorIndex, &mut Outline)>,
+) {
+    let hud_config = r!(hud_config.get());
+    let module_config = r!(module_config.get());
+    let is_helm = phase.is_in(&Phase::Helm);
+
+    // Highlight the neighbors of the selected slot if its module affects them.
+    let neighbors = player_deck
+        .reactor
+        .get(player_deck.slot_idx)
+        .filter(|slot| !matches!(slot.status, ModuleStatus::SlotEmpty))
+        .filter(|slot| !module_config.action(&slot.effect).adjacency.is_empty())
+        .map(|_| {
+            player_deck
+                .neighbors(module_config, player_deck.slot_idx)
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+
+    for (idx, mut outline) in &mut slot_query {
+        outline.color = if !is_helm {
+            Color::NONE
+        } else if player_deck.held_slot == Some(idx.0) {
+            hud_config.slot_held_color
+        } else if player_deck.slot_idx == idx.0 {
+            hud_config.slot_cursor_color
+        } else if neighbors.contains(&idx.0) {
+            hud_config.slot_neighbor_color
+        } else {
+            Color::NONE
+        };
+    }
+}
+
+fn insert_or_swap_module_on_drop(
+    trigger: Trigger<Pointer<DragDrop>>,
+    mut commands: Commands,
+    reactor_module_query: Query<&ReactorIndex>,
+    hand_module_query: Query<&HandIndex>,
+) {
+    rq!(matches!(trigger.event.button, PointerButton::Primary));
+    let target = rq!(trigger.get_target());
+    let slot_idx = rq!(reactor_module_query.get(target)).0;
+
+    if let Ok(hand_idx) = hand_module_query.get(trigger.event.dropped) {
+        commands.trigger(InsertReactorModule {
+            hand_idx: hand_idx.0,
+            slot_idx,
+        });
+    } else if let Ok(other_idx) = reactor_module_query.get(trigger.event.dropped) {
+        commands.trigger(SwapReactorModules(other_idx.0, slot_idx));
     }
 }
 
 fn discard_module_on_right_click(
     trigger: Trigger<Pointer<Click>>,
     mut commands: Commands,
-    phase: NextRef<Phase>,
     reactor_module_query: Query<&ReactorIndex>,
+) {
+    rq!(matches!(trigger.event.button, PointerButton::Secondary));
+    let target = rq!(trigger.get_target());
+    let idx = rq!(reactor_module_query.get(target));
+    commands.trigger(RemoveReactorModule(idx.0));
+}
+
+/// Remove the module at this reactor index to storage.
+#[derive(Event, Reflect, Debug)]
+pub struct RemoveReactorModule(pub usize);
+
+impl Configure for RemoveReactorModule {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_observer(remove_reactor_module);
+    }
+}
+
+fn remove_reactor_module(
+    trigger: Trigger<RemoveReactorModule>,
+    mut commands: Commands,
+    phase: NextRef<Phase>,
     mut player_deck: ResMut<PlayerDeck>,
+    mut helm_history: ResMut<HelmHistory>,
     audio_settings: Res<AudioSettings>,
     game_assets: Res<GameplayAssets>,
 ) {
-    rq!(matches!(trigger.event.button, PointerButton::Secondary));
     rq!(matches!(phase.get(), Some(Phase::Helm)));
-    let target = rq!(trigger.get_target());
-    let idx = rq!(reactor_module_query.get(target));
-    rq!(!matches!(
-        player_deck.reactor[idx.0].status,
-        ModuleStatus::SlotEmpty,
+    let idx = trigger.0;
+    let slot = rq!(player_deck.reactor.get(idx));
+    rq!(!matches!(slot.status, ModuleStatus::SlotEmpty));
+
+    helm_history.record(player_deck.clone());
+    player_deck.discard_module(idx);
+    commands.spawn((
+        sfx_audio(&audio_settings, game_assets.module_insert_sfx.clone(), 1.0),
+        DespawnOnExitState::<Level>::default(),
+    ));
+}
+
+/// Insert the module at a hand index into a reactor slot, replacing the module there.
+#[derive(Event, Reflect, Debug)]
+pub struct InsertReactorModule {
+    pub hand_idx: usize,
+    pub slot_idx: usize,
+}
+
+impl Configure for InsertReactorModule {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_observer(insert_reactor_module);
+    }
+}
+
+fn insert_reactor_module(
+    trigger: Trigger<InsertReactorModule>,
+    mut commands: Commands,
+    phase: NextRef<Phase>,
+    mut player_deck: ResMut<PlayerDeck>,
+    mut helm_history: ResMut<HelmHistory>,
+    audio_settings: Res<AudioSettings>,
+    game_assets: Res<GameplayAssets>,
+) {
+    rq!(matches!(phase.get(), Some(Phase::Helm)));
+    let snapshot = player_deck.clone();
+    rq!(player_deck.play_into_slot(trigger.hand_idx, trigger.slot_idx));
+    player_deck.held_slot = None;
+    helm_history.record(snapshot);
+
+    commands.spawn((
+        sfx_audio(&audio_settings, game_assets.module_insert_sfx.clone(), 1.0),
+        DespawnOnExitState::<Level>::default(),
     ));
+}
+
+/// Swap the modules in two reactor slots.
+#[derive(Event, Reflect, Debug)]
+pub struct SwapReactorModules(pub usize, pub usize);
+
+impl Configure for SwapReactorModules {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_observer(swap_reactor_modules);
+    }
+}
+
+fn swap_reactor_modules(
+    trigger: Trigger<SwapReactorModules>,
+    mut commands: Commands,
+    phase: NextRef<Phase>,
+    mut player_deck: ResMut<PlayerDeck>,
+    mut helm_history: ResMut<HelmHistory>,
+    audio_settings: Res<AudioSettings>,
+    game_assets: Res<GameplayAssets>,
+) {
+    rq!(matches!(phase.get(), Some(Phase::Helm)));
+    let snapshot = player_deck.clone();
+    rq!(player_deck.swap_slots(trigger.0, trigger.1));
+    player_deck.held_slot = None;
+    helm_history.record(snapshot);
 
-    player_deck.discard_module(idx.0);
     commands.spawn((
         sfx_audio(&audio_settings, game_assets.module_insert_sfx.clone(), 1.0),
         DespawnOnExitState::<Level>::default(),
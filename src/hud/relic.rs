@@ -0,0 +1,115 @@
+use crate::animation::shake::NodeShake;
+use crate::deck::PlayerDeck;
+use crate::hud::HudConfig;
+use crate::module::ModuleConfig;
+use crate::prelude::*;
+use crate::relic::RelicConfig;
+use crate::relic::TriggerRelics;
+
+pub(super) fn plugin(app: &mut App) {
+    app.configure::<(RelicBar, RelicIcon)>();
+}
+
+pub(super) fn relic_bar() -> impl Bundle {
+    (
+        Name::new("RelicBar"),
+        RelicBar::default(),
+        Node {
+            top: Vw(1.0),
+            left: Vw(1.0),
+            column_gap: Vw(0.5),
+            ..Node::ROW.abs()
+        },
+    )
+}
+
+/// Shows an icon for each of the player's relics.
+#[derive(Component, Reflect, Default, Debug)]
+#[reflect(Component)]
+pub struct RelicBar {
+    /// The relics currently shown.
+    relics: Vec<String>,
+}
+
+impl Configure for RelicBar {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            Update,
+            sync_relic_bar
+                .in_set(UpdateSystems::SyncLate)
+                .run_if(resource_changed::<PlayerDeck>.or(any_match_filter::<Added<Self>>)),
+        );
+    }
+}
+
+fn sync_relic_bar(
+    mut commands: Commands,
+    hud_config: ConfigRef<HudConfig>,
+    module_config: ConfigRef<ModuleConfig>,
+    relic_config: ConfigRef<RelicConfig>,
+    player_deck: Res<PlayerDeck>,
+    mut bar_query: Query<(Entity, &mut RelicBar)>,
+) {
+    let hud_config = r!(hud_config.get());
+    let module_config = r!(module_config.get());
+    let relic_config = r!(relic_config.get());
+
+    for (entity, mut bar) in &mut bar_query {
+        cq!(bar.relics != player_deck.relics);
+        bar.relics = player_deck.relics.clone();
+
+        commands
+            .entity(entity)
+            .despawn_related::<Children>()
+            .with_children(|parent| {
+                for key in &player_deck.relics {
+                    let relic = cq!(relic_config.relics.get(key));
+                    parent.spawn((
+                        Name::new("RelicIcon"),
+                        RelicIcon(key.clone()),
+                        ImageNode::from(relic.icon.clone()),
+                        Node {
+                            width: Vw(3.0),
+                            aspect_ratio: Some(1.0),
+                            ..default()
+                        },
+                        hud_config.module_shake,
+                        Tooltip::fixed(
+                            Anchor::BottomLeft,
+                            parse_rich(relic.description(module_config)),
+                        ),
+                    ));
+                }
+            });
+    }
+}
+
+/// The key of the relic in the relic config.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct RelicIcon(pub String);
+
+impl Configure for RelicIcon {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_observer(shake_relic_icons);
+    }
+}
+
+fn shake_relic_icons(
+    trigger: Trigger<TriggerRelics>,
+    hud_config: ConfigRef<HudConfig>,
+    relic_config: ConfigRef<RelicConfig>,
+    player_deck: Res<PlayerDeck>,
+    mut icon_query: Query<(&RelicIcon, &mut NodeShake)>,
+) {
+    let hud_config = r!(hud_config.get());
+    let relic_config = r!(relic_config.get());
+
+    for (icon, mut shake) in &mut icon_query {
+        let relic = cq!(relic_config.relics.get(&icon.0));
+        cq!(relic.reacts_to(&trigger.0, &player_deck));
+        shake.trauma += hud_config.module_flux_trauma.sample_clamped(0.0);
+    }
+}
@@ -0,0 +1,117 @@
+use crate::combat::death::Dead;
+use crate::combat::target::EnemyIndex;
+use crate::combat::target::PlayerTarget;
+use crate::combat::target::SelectTarget;
+use crate::deck::EnemyDeck;
+use crate::phase::Phase;
+use crate::prelude::*;
+use crate::ship::EnemyShip;
+
+pub(super) fn plugin(app: &mut App) {
+    app.configure::<(TargetBar, TargetButton)>();
+}
+
+pub(super) fn target_bar() -> impl Bundle {
+    (
+        Name::new("TargetBar"),
+        TargetBar,
+        Node {
+            display: Display::None,
+            column_gap: Vw(0.5),
+            ..Node::ROW.center()
+        },
+    )
+}
+
+/// Lists the living enemy ships so the player can pick which one to fire at.
+///
+/// Hidden unless there is more than one enemy ship to pick from.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct TargetBar;
+
+impl Configure for TargetBar {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            Update,
+            sync_target_bar.in_set(UpdateSystems::SyncLate).run_if(
+                resource_changed::<PlayerTarget>
+                    .or(any_match_filter::<Added<EnemyShip>>)
+                    .or(any_match_filter::<(Added<Dead>, With<EnemyShip>)>)
+                    .or(any_match_filter::<Added<Self>>),
+            ),
+        );
+    }
+}
+
+fn sync_target_bar(
+    mut commands: Commands,
+    player_target: Res<PlayerTarget>,
+    enemy_query: Query<(Entity, &EnemyIndex, &EnemyDeck, Has<Dead>), With<EnemyShip>>,
+    mut bar_query: Query<(Entity, &mut Node), With<TargetBar>>,
+) {
+    let target = player_target.find(
+        enemy_query
+            .iter()
+            .map(|(entity, idx, _, is_dead)| (entity, idx, is_dead)),
+    );
+    let mut enemies = enemy_query
+        .iter()
+        .filter(|(.., is_dead)| !is_dead)
+        .collect::<Vec<_>>();
+    enemies.sort_by_key(|(_, idx, ..)| **idx);
+
+    for (entity, mut node) in &mut bar_query {
+        node.display = if enemies.len() > 1 {
+            Display::Flex
+        } else {
+            Display::None
+        };
+
+        commands
+            .entity(entity)
+            .despawn_related::<Children>()
+            .with_children(|parent| {
+                parent.spawn(widget::tiny_label("[b]Target"));
+                for &(enemy, idx, enemy_deck, _) in &enemies {
+                    parent
+                        .spawn((
+                            widget::tiny_button((idx.0 + 1).to_string(), click_target_button),
+                            TargetButton(idx.0),
+                            Tooltip::fixed(
+                                Anchor::CenterLeft,
+                                parse_rich(format!("[b]{}", enemy_deck.display_name(idx.0))),
+                            ),
+                        ))
+                        // The current target can't be picked again.
+                        .insert(InteractionDisabled(Some(enemy) == target));
+                }
+            });
+    }
+}
+
+/// Targets the enemy ship at this index when clicked during the helm phase.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct TargetButton(pub usize);
+
+impl Configure for TargetButton {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+    }
+}
+
+fn click_target_button(
+    trigger: Trigger<Pointer<Click>>,
+    mut commands: Commands,
+    phase: CurrentRef<Phase>,
+    button_query: Query<(&TargetButton, &InteractionDisabled)>,
+) {
+    rq!(matches!(trigger.event.button, PointerButton::Primary));
+    rq!(phase.is_in(&Phase::Helm));
+    let target = r!(trigger.get_target());
+    let (button, disabled) = r!(button_query.get(target));
+    rq!(!disabled.0);
+    commands.trigger(SelectTarget(button.0));
+}
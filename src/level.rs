@@ -1,40 +1,115 @@
+use std::borrow::Cow;
+
 use crate::animation::shake::Shake;
+use crate::boss::EnemyBehavior;
+use crate::combat::health::Health;
+use crate::combat::shield::Shield;
+use crate::combat::status::DamageOverTime;
+use crate::combat::status::StatusEffects;
+use crate::combat::target::EnemyIndex;
+use crate::combat::target::PlayerTarget;
 use crate::core::audio::AudioSettings;
 use crate::core::audio::sfx_audio;
 use crate::core::camera::CameraRoot;
 use crate::core::physics::GameLayer;
 use crate::deck::DeckConfig;
-use crate::deck::EnemyDeck;
 use crate::deck::PlayerDeck;
+use crate::deck::SelectedDeck;
+use crate::endless::EndlessConfig;
+use crate::endless::EndlessMode;
+use crate::endless::noise_background;
 use crate::hud;
 use crate::hud::HudConfig;
 use crate::menu::Menu;
+use crate::modifier::LevelModifier;
+use crate::modifier::LevelModifiers;
 use crate::prelude::*;
+use crate::rng::GameRng;
+use crate::save::EnemySave;
+use crate::save::Resume;
 use crate::screen::gameplay::GameplayAssets;
+use crate::ship::EnemyShip;
 use crate::ship::ShipConfig;
 use crate::ship::enemy_ship;
 use crate::ship::player_ship;
 use crate::theme::toast::Toaster;
+use crate::theme::toast::toast;
 
 pub(super) fn plugin(app: &mut App) {
-    app.configure::<(ConfigHandle<LevelConfig>, Level)>();
+    app.configure::<(
+        ConfigHandle<LevelConfig>,
+        Level,
+        CurrentLevelSetup,
+        EnemyWave,
+    )>();
 }
 
 #[derive(Asset, Reflect, Serialize, Deserialize, Default)]
 #[serde(deny_unknown_fields, default)]
 pub struct LevelConfig {
     pub levels: Vec<LevelSetup>,
+    /// How to generate stars past the authored levels in endless mode.
+    pub endless: EndlessConfig,
 }
 
 impl Config for LevelConfig {
     const FILE: &'static str = "level.ron";
 }
 
-#[derive(Reflect, Serialize, Deserialize, Default)]
+impl LevelConfig {
+    /// The setup of a level, generating a star past the authored levels in endless mode.
+    pub fn level_setup(
+        &self,
+        level: usize,
+        endless: EndlessMode,
+        game_rng: &GameRng,
+    ) -> Option<Cow<'_, LevelSetup>> {
+        if let Some(level_setup) = self.levels.get(level) {
+            return Some(Cow::Borrowed(level_setup));
+        }
+        rq!(endless.0);
+
+        let depth = level - self.levels.len();
+        Some(Cow::Owned(
+            self.endless.generate(depth, &mut game_rng.star(level)),
+        ))
+    }
+
+    /// Whether a level was generated rather than authored.
+    pub fn is_generated(&self, level: usize) -> bool {
+        level >= self.levels.len()
+    }
+}
+
+#[derive(Reflect, Serialize, Deserialize, Clone, Default)]
 #[serde(deny_unknown_fields, default)]
 pub struct LevelSetup {
     pub name: String,
-    pub enemy_deck: EnemyDeck,
+    /// The enemy ships of each wave. The next wave arrives once the current wave is defeated.
+    pub waves: Vec<Vec<EnemySetup>>,
+    /// Win the run once this level is cleared, even if more levels follow.
+    pub victory: bool,
+    /// Environmental hazards that change the rules of the level.
+    pub modifiers: LevelModifiers,
+}
+
+#[derive(Reflect, Serialize, Deserialize, Clone, Debug)]
+#[serde(deny_unknown_fields, default)]
+pub struct EnemySetup {
+    /// The ship's starting position.
+    pub position: Vec2,
+    pub deck: EnemyDeck,
+    pub behavior: EnemyBehavior,
+}
+
+impl Default for EnemySetup {
+    fn default() -> Self {
+        Self {
+            position: vec2(59.0, 93.0),
+            deck: default(),
+            behavior: default(),
+        }
+    }
 }
 
 #[derive(State, Reflect, Copy, Clone, Default, Eq, PartialEq, Debug)]
@@ -51,7 +126,7 @@ impl Configure for Level {
             Level::ANY.on_edge(
                 (reset_player_deck, reset_camera),
                 (
-                    (set_up_decks, spawn_level).chain(),
+                    (set_current_level_setup, set_up_decks, spawn_level).chain(),
                     (Menu::release, Menu::clear).chain(),
                 ),
             ),
@@ -59,6 +134,30 @@ impl Configure for Level {
     }
 }
 
+/// The setup of the current level, generated once when the level is entered.
+#[derive(Resource, Reflect, Default)]
+#[reflect(Resource)]
+pub struct CurrentLevelSetup(pub LevelSetup);
+
+impl Configure for CurrentLevelSetup {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.init_resource::<Self>();
+    }
+}
+
+pub fn set_current_level_setup(
+    level: NextRef<Level>,
+    level_config: ConfigRef<LevelConfig>,
+    endless: Res<EndlessMode>,
+    game_rng: Res<GameRng>,
+    mut current_level_setup: ResMut<CurrentLevelSetup>,
+) {
+    let level = r!(level.get()).0;
+    let level_config = r!(level_config.get());
+    current_level_setup.0 = r!(level_config.level_setup(level, *endless, &game_rng)).into_owned();
+}
+
 fn reset_camera(camera_root: Res<CameraRoot>, mut camera_query: Query<&mut Shake>) {
     let mut shake = r!(camera_query.get_mut(camera_root.primary));
     *shake = default();
@@ -70,52 +169,95 @@ fn reset_player_deck(mut player_deck: ResMut<PlayerDeck>) {
 
 fn set_up_decks(
     level: NextRef<Level>,
-    level_config: ConfigRef<LevelConfig>,
+    level_setup: Res<CurrentLevelSetup>,
     deck_config: ConfigRef<DeckConfig>,
+    resume: Option<Res<Resume>>,
+    selected_deck: Res<SelectedDeck>,
     mut player_deck: ResMut<PlayerDeck>,
-    mut enemy_deck: ResMut<EnemyDeck>,
 ) {
     let level = r!(level.get()).0;
-    let level_config = r!(level_config.get());
-    let level_setup = r!(level_config.levels.get(level));
+    let level_setup = &level_setup.0;
     let deck_config = r!(deck_config.get());
 
-    if level == 0 {
-        *player_deck = deck_config.player_decks[0].clone();
+    if level == 0 && resume.is_none() {
+        *player_deck = r!(deck_config
+            .player_decks
+            .get(selected_deck.0)
+            .or(deck_config.player_decks.first()))
+        .clone();
     }
-    *enemy_deck = level_setup.enemy_deck.clone();
+    player_deck.extra_heat = level_setup.modifiers.extra_heat();
 }
 
-fn spawn_level(
+pub fn spawn_level(
     mut commands: Commands,
     game_assets: Res<GameplayAssets>,
     level: NextRef<Level>,
     level_config: ConfigRef<LevelConfig>,
+    level_setup: Res<CurrentLevelSetup>,
     hud_config: ConfigRef<HudConfig>,
     ship_config: ConfigRef<ShipConfig>,
+    deck_config: ConfigRef<DeckConfig>,
     player_deck: Res<PlayerDeck>,
-    enemy_deck: Res<EnemyDeck>,
+    game_rng: Res<GameRng>,
+    mut images: ResMut<Assets<Image>>,
+    mut enemy_wave: ResMut<EnemyWave>,
+    mut player_target: ResMut<PlayerTarget>,
+    resume: Option<Res<Resume>>,
 ) {
     let level = r!(level.get()).0;
     let level_config = r!(level_config.get());
-    let level_setup = r!(level_config.levels.get(level));
+    let level_setup = &level_setup.0;
     let hud_config = r!(hud_config.get());
     let ship_config = r!(ship_config.get());
+    let deck_config = r!(deck_config.get());
+    let mut player_health = Health::new(player_deck.max_health);
+    player_health.current = (player_health.max - player_deck.hull_damage).max(1.0);
+    let mut player_shield = None;
+    enemy_wave.0 = 0;
+    player_target.0 = 0;
+    let mut saved_enemies = None;
+    if let Some(resume) = &resume {
+        player_health.current = resume.0.player_health.unwrap_or(player_health.current);
+        if resume.0.player_shield > f32::EPSILON {
+            player_shield = Some(Shield {
+                current: resume.0.player_shield,
+                ..default()
+            });
+        }
+        enemy_wave.0 = resume.0.wave;
+        player_target.0 = resume.0.target;
+        if !resume.0.enemies.is_empty() {
+            saved_enemies = Some(resume.0.enemies.as_slice());
+        }
+    }
 
-    commands.spawn(background(&game_assets, level));
+    commands.spawn(background(if level_config.is_generated(level) {
+        images.add(noise_background(&mut game_rng.star(level)))
+    } else {
+        authored_background(&game_assets, level)
+    }));
     commands.spawn((
         hud::hud(hud_config, &game_assets),
         DespawnOnExitState::<Level>::default(),
     ));
-    commands.spawn((
-        widget::tiny_label(format!("[b]{}", level_setup.name)),
-        Node {
-            top: Vw(1.0),
-            right: Vw(1.0),
-            ..Node::DEFAULT.abs()
-        },
-        DespawnOnExitState::<Level>::default(),
-    ));
+    commands
+        .spawn((
+            Name::new("LevelName"),
+            Node {
+                top: Vw(1.0),
+                right: Vw(1.0),
+                column_gap: Vw(0.5),
+                ..Node::ROW.center().abs()
+            },
+            DespawnOnExitState::<Level>::default(),
+        ))
+        .with_children(|parent| {
+            for modifier in &level_setup.modifiers.0 {
+                parent.spawn(modifier_badge(modifier));
+            }
+            parent.spawn(widget::tiny_label(format!("[b]{}", level_setup.name)));
+        });
     commands.spawn((
         Name::new("Toaster"),
         Toaster,
@@ -126,16 +268,31 @@ fn spawn_level(
         },
         DespawnOnExitState::<Level>::default(),
     ));
-    commands.spawn((
-        player_ship(ship_config, &game_assets, player_deck.max_health),
+    let mut player = commands.spawn((
+        player_ship(ship_config, deck_config, &player_deck, player_health),
         Transform::from_xyz(61.0, -46.0, 2.0),
         DespawnOnExitState::<Level>::default(),
     ));
-    commands.spawn((
-        enemy_ship(ship_config, &game_assets, enemy_deck.max_health),
-        Transform::from_xyz(59.0, 93.0, 0.0),
-        DespawnOnExitState::<Level>::default(),
-    ));
+    if let Some(shield) = player_shield {
+        player.insert(shield);
+    }
+    if let Some(resume) = &resume {
+        insert_saved_statuses(
+            &mut player,
+            &resume.0.player_damage_over_time,
+            &resume.0.player_statuses,
+        );
+    }
+    if let Some(wave) = level_setup.waves.get(enemy_wave.0) {
+        spawn_wave(
+            &mut commands,
+            ship_config,
+            &game_assets,
+            wave,
+            level_setup.modifiers.extra_heat(),
+            saved_enemies,
+        );
+    }
     commands
         .spawn((
             Name::new("EnemyEscapeSensor"),
@@ -146,44 +303,225 @@ fn spawn_level(
             CollisionEventsEnabled,
             DespawnOnExitState::<Level>::default(),
         ))
-        .observe(win_level_on_enemy_escape);
+        .observe(despawn_enemy_on_escape);
 }
 
-fn win_level_on_enemy_escape(
-    _: Trigger<OnCollisionStart>,
+/// Spawn the enemy ships of a wave, restoring their state from a save if given.
+fn spawn_wave(
+    commands: &mut Commands,
+    ship_config: &ShipConfig,
+    game_assets: &GameplayAssets,
+    wave: &[EnemySetup],
+    extra_heat: f32,
+    saved_enemies: Option<&[Option<EnemySave>]>,
+) {
+    for (idx, setup) in wave.iter().enumerate() {
+        let mut deck = setup.deck.clone();
+        let mut behavior = setup.behavior.clone();
+        let mut health = Health::new(deck.max_health);
+        let mut shield = deck.shield.clone();
+        if let Some(saved_enemies) = saved_enemies {
+            // Enemies that were defeated before the save stay defeated.
+            let Some(Some(saved)) = saved_enemies.get(idx) else {
+                continue;
+            };
+            health.current = saved.health;
+            for _ in 0..saved.phase {
+                behavior.enter_next_phase(&mut deck);
+            }
+            if let Some(current) = saved.shield {
+                shield.get_or_insert_default().current = current;
+            }
+            if let (Some(reactor), Some(saved_reactor)) = (&mut deck.reactor, &saved.reactor) {
+                reactor.deck = saved_reactor.clone();
+            }
+        }
+        if let Some(reactor) = &mut deck.reactor {
+            reactor.deck.extra_heat = extra_heat;
+        }
+
+        let mut enemy = commands.spawn((
+            enemy_ship(ship_config, game_assets, health),
+            EnemyIndex(idx),
+            Transform::from_translation(setup.position.extend(0.0)),
+            DespawnOnExitState::<Level>::default(),
+        ));
+        if let Some(shield) = shield {
+            enemy.insert(shield);
+        }
+        if let Some(armor) = deck.armor.clone() {
+            enemy.insert(armor);
+        }
+        if let Some(Some(saved)) = saved_enemies.and_then(|x| x.get(idx)) {
+            insert_saved_statuses(&mut enemy, &saved.damage_over_time, &saved.statuses);
+        }
+        enemy.insert((deck, behavior));
+    }
+}
+
+/// Restore a ship's damage over time and statuses from a save.
+fn insert_saved_statuses(
+    entity: &mut EntityCommands,
+    damage_over_time: &DamageOverTime,
+    statuses: &StatusEffects,
+) {
+    if !damage_over_time.0.is_empty() {
+        entity.insert(damage_over_time.clone());
+    }
+    if !statuses.0.is_empty() {
+        entity.insert(statuses.clone());
+    }
+}
+
+fn despawn_enemy_on_escape(
+    trigger: Trigger<OnCollisionStart>,
     mut commands: Commands,
     audio_settings: Res<AudioSettings>,
     game_assets: Res<GameplayAssets>,
-    mut menu: ResMut<NextStateStack<Menu>>,
+    enemy_query: Query<(), With<EnemyShip>>,
 ) {
+    rq!(enemy_query.contains(trigger.collider));
+    commands.entity(trigger.collider).try_despawn();
     commands.spawn((
         sfx_audio(&audio_settings, game_assets.ship_death_sfx.clone(), 1.0),
         DespawnOnExitState::<Level>::default(),
     ));
+}
+
+/// The index of the current enemy wave in the level setup.
+#[derive(Resource, Reflect, Default, Debug)]
+#[reflect(Resource)]
+pub struct EnemyWave(pub usize);
+
+impl Configure for EnemyWave {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.init_resource::<Self>();
+        app.add_systems(
+            Update,
+            Level::ANY.on_update(
+                spawn_next_wave
+                    .in_set(UpdateSystems::Update)
+                    .run_if(Menu::is_disabled.and(not(any_with_component::<EnemyShip>))),
+            ),
+        );
+    }
+}
+
+fn spawn_next_wave(
+    mut commands: Commands,
+    game_assets: Res<GameplayAssets>,
+    level: CurrentRef<Level>,
+    level_config: ConfigRef<LevelConfig>,
+    level_setup: Res<CurrentLevelSetup>,
+    ship_config: ConfigRef<ShipConfig>,
+    endless: Res<EndlessMode>,
+    resume: Option<Res<Resume>>,
+    mut enemy_wave: ResMut<EnemyWave>,
+    mut player_target: ResMut<PlayerTarget>,
+    mut menu: ResMut<NextStateStack<Menu>>,
+) {
+    let level = r!(level.get()).0;
+    let level_config = r!(level_config.get());
+    let level_setup = &level_setup.0;
+    let ship_config = r!(ship_config.get());
+
+    // Reopen the level boundary menu the campaign was saved in.
+    if let Some(saved_menu) = resume.as_ref().and_then(|x| x.0.menu.as_ref()) {
+        menu.push(saved_menu.menu());
+        menu.acquire();
+        return;
+    }
+
+    // Win the level once every wave is defeated. Endless runs only end in defeat.
+    enemy_wave.0 += 1;
+    let Some(wave) = level_setup.waves.get(enemy_wave.0) else {
+        menu.push(
+            if !endless.0 && (level_setup.victory || level + 1 >= level_config.levels.len()) {
+                Menu::Victory
+            } else {
+                Menu::Upgrade
+            },
+        );
+        menu.acquire();
+        return;
+    };
+
+    spawn_wave(
+        &mut commands,
+        ship_config,
+        &game_assets,
+        wave,
+        level_setup.modifiers.extra_heat(),
+        None,
+    );
+    player_target.0 = 0;
+    commands.spawn((
+        toast(format!(
+            "[b]Wave {} of {}[r]\n\
+            More ships are closing in.",
+            enemy_wave.0 + 1,
+            level_setup.waves.len(),
+        )),
+        DespawnOnExitState::<Level>::default(),
+    ));
+}
 
-    menu.push(Menu::Upgrade);
-    menu.acquire();
+fn modifier_badge(modifier: &LevelModifier) -> impl Bundle {
+    (
+        Name::new("ModifierBadge"),
+        Node {
+            padding: UiRect::axes(Vw(0.5), Vw(0.2)),
+            border: UiRect::all(Vw(0.1)),
+            ..default()
+        },
+        ThemeColor::Monitor.set::<BackgroundColor>(),
+        ThemeColor::MonitorDimText.set::<BorderColor>(),
+        BorderRadius::all(Vw(0.5)),
+        Tooltip::fixed(
+            Anchor::BottomCenter,
+            parse_rich(format!(
+                "[b]{}[r]\n\n{}",
+                modifier.name(),
+                modifier.description()
+            )),
+        ),
+        children![widget::label_base(
+            Vw(1.35),
+            ThemeColor::MonitorText,
+            JustifyText::Center,
+            1.2,
+            modifier.name(),
+        )],
+    )
 }
 
-fn background(game_assets: &GameplayAssets, level: usize) -> impl Bundle {
+fn authored_background(game_assets: &GameplayAssets, level: usize) -> Handle<Image> {
+    match level {
+        0 => &game_assets.bg_level0,
+        1 => &game_assets.bg_level1,
+        2 => &game_assets.bg_level2,
+        3 => &game_assets.bg_level3,
+        4 => &game_assets.bg_level4,
+        5 => &game_assets.bg_level5,
+        6 => &game_assets.bg_level6,
+        7 => &game_assets.bg_level7,
+        8 => &game_assets.bg_level8,
+        9 => &game_assets.bg_level9,
+        _ => &game_assets.bg_level0,
+    }
+    .clone()
+}
+
+fn background(image: Handle<Image>) -> impl Bundle {
     (
         Name::new("Background"),
-        Sprite::from_image(
-            match level {
-                0 => &game_assets.bg_level0,
-                1 => &game_assets.bg_level1,
-                2 => &game_assets.bg_level2,
-                3 => &game_assets.bg_level3,
-                4 => &game_assets.bg_level4,
-                5 => &game_assets.bg_level5,
-                6 => &game_assets.bg_level6,
-                7 => &game_assets.bg_level7,
-                8 => &game_assets.bg_level8,
-                9 => &game_assets.bg_level9,
-                _ => &game_assets.bg_level0,
-            }
-            .clone(),
-        ),
+        Sprite {
+            image,
+            // Generated backgrounds are drawn at a lower resolution.
+            custom_size: Some(vec2(520.0, 310.0)),
+            ..default()
+        },
         Transform::from_xyz(0.0, 0.0, -2.0),
         DespawnOnExitState::<Level>::default(),
         children![(
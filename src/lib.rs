@@ -0,0 +1,68 @@
+// Support configuring Bevy lints within code.
+#![cfg_attr(bevy_lint, feature(register_tool), register_tool(bevy))]
+
+pub mod animation;
+pub mod boss;
+pub mod combat;
+pub mod core;
+pub mod deck;
+pub mod effect;
+pub mod endless;
+pub mod history;
+pub mod hud;
+pub mod level;
+pub mod menu;
+pub mod modifier;
+pub mod module;
+pub mod phase;
+pub mod prelude;
+pub mod projectile;
+pub mod relic;
+pub mod replay;
+pub mod rng;
+pub mod save;
+pub mod screen;
+pub mod ship;
+#[cfg(any(test, feature = "balance"))]
+pub mod sim;
+pub mod stats;
+pub mod theme;
+pub mod util;
+
+use crate::prelude::*;
+
+pub fn plugin(app: &mut App) {
+    // Add core plugins.
+    app.add_plugins(core::plugin);
+
+    // Add other plugins.
+    app.add_plugins((
+        (
+            animation::plugin,
+            boss::plugin,
+            combat::plugin,
+            deck::plugin,
+            effect::plugin,
+            endless::plugin,
+            history::plugin,
+            hud::plugin,
+            level::plugin,
+            menu::plugin,
+            modifier::plugin,
+            module::plugin,
+            phase::plugin,
+        ),
+        (
+            projectile::plugin,
+            relic::plugin,
+            replay::plugin,
+            rng::plugin,
+            save::plugin,
+            screen::plugin,
+            ship::plugin,
+            stats::plugin,
+            theme::plugin,
+            util::plugin,
+        ),
+    ));
+}
@@ -3,47 +3,8 @@
 // Disable console on windows for non-dev builds.
 #![cfg_attr(not(feature = "dev"), windows_subsystem = "windows")]
 
-mod animation;
-mod combat;
-mod core;
-mod deck;
-mod hud;
-mod level;
-mod menu;
-mod module;
-mod phase;
-mod prelude;
-mod projectile;
-mod screen;
-mod ship;
-mod stats;
-mod theme;
-mod util;
-
-use crate::prelude::*;
-
-pub fn plugin(app: &mut App) {
-    // Add core plugins.
-    app.add_plugins(core::plugin);
-
-    // Add other plugins.
-    app.add_plugins((
-        animation::plugin,
-        combat::plugin,
-        deck::plugin,
-        hud::plugin,
-        level::plugin,
-        menu::plugin,
-        module::plugin,
-        phase::plugin,
-        projectile::plugin,
-        screen::plugin,
-        ship::plugin,
-        stats::plugin,
-        theme::plugin,
-        util::plugin,
-    ));
-}
+use flux_pursuit::plugin;
+use flux_pursuit::prelude::*;
 
 fn main() -> AppExit {
     run()
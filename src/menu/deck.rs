@@ -0,0 +1,336 @@
+use crate::deck::PlayerDeck;
+use crate::hud::module::module;
+use crate::menu::Menu;
+use crate::menu::MenuRoot;
+use crate::module::Condition;
+use crate::module::Module;
+use crate::module::ModuleConfig;
+use crate::module::ModuleStatus;
+use crate::prelude::*;
+use crate::projectile::ProjectileConfig;
+use crate::screen::gameplay::GameplayAssets;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(StateFlush, Menu::Deck.on_enter(spawn_deck_menu));
+
+    app.configure::<(DeckGrouping, GroupingSelector, DeckGrid, DrawChances)>();
+}
+
+fn spawn_deck_menu(mut commands: Commands, menu_root: Res<MenuRoot>) {
+    commands
+        .entity(menu_root.ui)
+        .with_child(widget::popup(children![
+            widget::header("[b]Your deck"),
+            widget::selector(GroupingSelector, previous_grouping, next_grouping),
+            (
+                Name::new("DeckInfo"),
+                Node {
+                    margin: UiRect::top(Vw(2.0)),
+                    column_gap: Vw(3.0),
+                    align_items: AlignItems::Start,
+                    ..Node::ROW
+                },
+                children![
+                    (
+                        Name::new("DeckGrid"),
+                        DeckGrid,
+                        Node {
+                            row_gap: Vw(1.0),
+                            ..Node::COLUMN.grow()
+                        },
+                    ),
+                    (
+                        widget::small_label(""),
+                        DrawChances,
+                        Node {
+                            width: Vw(22.0),
+                            ..default()
+                        },
+                    ),
+                ],
+            ),
+            widget::row_of_buttons(children![widget::wide_button("Back", go_back)]),
+        ]));
+}
+
+fn go_back(trigger: Trigger<Pointer<Click>>, mut menu: ResMut<NextStateStack<Menu>>) {
+    rq!(matches!(trigger.event.button, PointerButton::Primary));
+    menu.pop();
+}
+
+/// How the modules in the deck viewer are grouped.
+#[derive(Resource, Reflect, Copy, Clone, Default, Eq, PartialEq, Debug)]
+#[reflect(Resource)]
+enum DeckGrouping {
+    #[default]
+    Location,
+    Condition,
+    Effect,
+}
+
+impl Configure for DeckGrouping {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.init_resource::<Self>();
+    }
+}
+
+impl DeckGrouping {
+    const ALL: [Self; 3] = [Self::Location, Self::Condition, Self::Effect];
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Location => "Group by location",
+            Self::Condition => "Group by condition",
+            Self::Effect => "Group by effect",
+        }
+    }
+
+    fn idx(self) -> usize {
+        Self::ALL
+            .iter()
+            .position(|&x| x == self)
+            .unwrap_or_default()
+    }
+}
+
+fn previous_grouping(trigger: Trigger<Pointer<Click>>, mut grouping: ResMut<DeckGrouping>) {
+    rq!(matches!(trigger.event.button, PointerButton::Primary));
+    *grouping = DeckGrouping::ALL[grouping.idx().saturating_sub(1)];
+}
+
+fn next_grouping(trigger: Trigger<Pointer<Click>>, mut grouping: ResMut<DeckGrouping>) {
+    rq!(matches!(trigger.event.button, PointerButton::Primary));
+    *grouping = DeckGrouping::ALL[(grouping.idx() + 1).min(DeckGrouping::ALL.len() - 1)];
+}
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct GroupingSelector;
+
+impl Configure for GroupingSelector {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            Update,
+            Menu::Deck.on_update(update_grouping_selector.in_set(UpdateSystems::Update)),
+        );
+    }
+}
+
+fn update_grouping_selector(
+    grouping: Res<DeckGrouping>,
+    selector_query: Query<Entity, With<GroupingSelector>>,
+    children_query: Query<&Children>,
+    mut text_query: Query<&mut RichText>,
+    mut disabled_query: Query<&mut InteractionDisabled>,
+) {
+    let idx = grouping.idx();
+
+    for entity in &selector_query {
+        let children = c!(children_query.get(entity))
+            .into_iter()
+            .collect::<Vec<_>>();
+
+        let left = **c!(children.first());
+        c!(disabled_query.get_mut(left)).0 = idx == 0;
+
+        let mid = **c!(children.get(1));
+        let mid_children = c!(children_query.get(mid));
+        let label = *c!(mid_children.first());
+        c!(text_query.get_mut(label)).sections = parse_rich(format!("[b]{}", grouping.name()));
+
+        let right = **c!(children.get(2));
+        c!(disabled_query.get_mut(right)).0 = idx + 1 >= DeckGrouping::ALL.len();
+    }
+}
+
+/// Lists every module in the deck as module tiles, grouped by [`DeckGrouping`].
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct DeckGrid;
+
+impl Configure for DeckGrid {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            Update,
+            Menu::Deck.on_update(
+                sync_deck_grid
+                    .in_set(UpdateSystems::SyncLate)
+                    .run_if(resource_changed::<DeckGrouping>.or(any_match_filter::<Added<Self>>)),
+            ),
+        );
+    }
+}
+
+fn sync_deck_grid(
+    mut commands: Commands,
+    game_assets: Res<GameplayAssets>,
+    module_config: ConfigRef<ModuleConfig>,
+    projectile_config: ConfigRef<ProjectileConfig>,
+    grouping: Res<DeckGrouping>,
+    player_deck: Res<PlayerDeck>,
+    grid_query: Query<Entity, With<DeckGrid>>,
+) {
+    let module_config = r!(module_config.get());
+    let projectile_config = r!(projectile_config.get());
+    let groups = group_modules(&player_deck, module_config, *grouping);
+
+    for entity in &grid_query {
+        commands
+            .entity(entity)
+            .despawn_related::<Children>()
+            .with_children(|parent| {
+                for (name, modules) in &groups {
+                    parent.spawn(widget::small_label(format!(
+                        "[b]{name}[r] ({})",
+                        modules.len(),
+                    )));
+                    parent
+                        .spawn((
+                            Name::new("DeckGroup"),
+                            Node {
+                                flex_wrap: FlexWrap::Wrap,
+                                row_gap: Vw(0.5),
+                                column_gap: Vw(0.5),
+                                ..Node::ROW
+                            },
+                        ))
+                        .with_children(|parent| {
+                            for slot in modules {
+                                parent.spawn((
+                                    module(
+                                        &game_assets,
+                                        module_config,
+                                        slot,
+                                        player_deck.heat_capacity,
+                                    ),
+                                    Tooltip::fixed(
+                                        Anchor::TopCenter,
+                                        parse_rich(slot.description(
+                                            module_config,
+                                            projectile_config,
+                                            player_deck.heat_capacity,
+                                        )),
+                                    ),
+                                ));
+                            }
+                        });
+                }
+            });
+    }
+}
+
+/// Group the modules in the deck, sorting them by condition and effect within each group.
+fn group_modules<'a>(
+    player_deck: &'a PlayerDeck,
+    module_config: &ModuleConfig,
+    grouping: DeckGrouping,
+) -> Vec<(String, Vec<&'a Module>)> {
+    let condition_name = |x: &Module| Condition::parse(&x.condition).name(module_config);
+    let effect_name = |x: &Module| module_config.action(&x.effect).effect_name.clone();
+    let sort = |modules: &mut Vec<&Module>| {
+        modules.sort_by_cached_key(|x| (condition_name(x), effect_name(x)));
+    };
+    let filled = |modules: &'a [Module]| {
+        modules
+            .iter()
+            .filter(|x| !matches!(x.status, ModuleStatus::SlotEmpty))
+            .collect::<Vec<_>>()
+    };
+
+    let mut groups = match grouping {
+        DeckGrouping::Location => vec![
+            ("Reactor".to_string(), filled(&player_deck.reactor)),
+            ("Hand".to_string(), filled(&player_deck.hand)),
+            ("Storage".to_string(), filled(&player_deck.storage)),
+        ],
+        DeckGrouping::Condition | DeckGrouping::Effect => {
+            let mut groups = Vec::<(String, Vec<&Module>)>::new();
+            for slot in [
+                &player_deck.reactor,
+                &player_deck.hand,
+                &player_deck.storage,
+            ]
+            .into_iter()
+            .flat_map(|x| filled(x))
+            {
+                let key = if grouping == DeckGrouping::Condition {
+                    condition_name(slot)
+                } else {
+                    effect_name(slot)
+                };
+                match groups.iter_mut().find(|(name, _)| *name == key) {
+                    Some((_, modules)) => modules.push(slot),
+                    None => groups.push((key, vec![slot])),
+                }
+            }
+            groups.sort_by(|a, b| a.0.cmp(&b.0));
+            groups
+        },
+    };
+    groups.retain(|(_, modules)| !modules.is_empty());
+    for (_, modules) in &mut groups {
+        sort(modules);
+    }
+
+    groups
+}
+
+/// Shows the chance to draw each action in the next setup phase.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct DrawChances;
+
+impl Configure for DrawChances {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            Update,
+            Menu::Deck.on_update(
+                sync_draw_chances
+                    .in_set(UpdateSystems::SyncLate)
+                    .run_if(resource_changed::<PlayerDeck>.or(any_match_filter::<Added<Self>>)),
+            ),
+        );
+    }
+}
+
+fn sync_draw_chances(
+    module_config: ConfigRef<ModuleConfig>,
+    player_deck: Res<PlayerDeck>,
+    mut text_query: Query<&mut RichText, With<DrawChances>>,
+) {
+    let module_config = r!(module_config.get());
+
+    let mut actions = module_config
+        .actions
+        .iter()
+        .filter(|(key, _)| {
+            player_deck
+                .storage
+                .iter()
+                .any(|x| &x.condition == *key || &x.effect == *key)
+        })
+        .collect::<Vec<_>>();
+    actions.sort_by(|a, b| a.1.effect_name.cmp(&b.1.effect_name));
+
+    let mut lines = vec![format!(
+        "[b]Next draw[r]\n\nThe next setup will draw {} of {} modules in storage.\n",
+        player_deck.next_draw_count(),
+        player_deck.storage.len(),
+    )];
+    for (key, action) in actions {
+        let chance = player_deck.draw_chance(|x| &x.condition == key || &x.effect == key);
+        lines.push(format!(
+            "- [b]{}:[r] {}%",
+            action.effect_name,
+            (100.0 * chance).round(),
+        ));
+    }
+
+    for mut text in &mut text_query {
+        text.sections = parse_rich(lines.join("\n"));
+    }
+}
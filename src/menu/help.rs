@@ -16,14 +16,30 @@ fn spawn_help_menu(mut commands: Commands, menu_root: Res<MenuRoot>, player_deck
                 \n\
                 [b]The Helm[r] (bottom)\n\
                 - Left click to insert a module into the reactor.\n\
+                - Drag a module onto a reactor slot to replace it.\n\
+                - Drag between reactor slots to swap them.\n\
                 - Right click to remove a module from the reactor or helm.\n\
+                - Press Ctrl+Z to undo and Ctrl+Y to redo.\n\
                 - Press Space to end your turn.\n\
+                - Press Tab or click storage to view your whole deck.\n\
                 \n\
                 [b]The Reactor[r] (left)\n\
                 - Each module has a [b]Condition[r] -> [b]Effect[r].\n\
                 - The reactor always activates the first matching module.\n\
+                - Some conditions match any action, all but one, or a long chain.\n\
+                - Some modules affect their neighbors in the reactor grid.\n\
                 - Modules gain heat equal to the length of the current chain.\n\
                 - Flux tracks the longest chain and boosts your power.\n\
+                \n\
+                [b]The Enemy[r] (right)\n\
+                - Some stars are guarded by several ships or waves of ships.\n\
+                - Click a number under [b]Target[r] to pick which ship your chain fires at.\n\
+                - The next wave arrives once every ship in the current wave is defeated.\n\
+                \n\
+                [b]The Salvage Yard[r] (between stars)\n\
+                - Damage enemies and clear stars to earn salvage.\n\
+                - Spend it on modules, upgrades, scrapping modules and hull repairs.\n\
+                - Relics last for the whole run. Hover over them to see what they do.\n\
                 \n",
                 player_deck.name,
             ),),
@@ -1,9 +1,6 @@
 use crate::menu::Menu;
 use crate::menu::MenuRoot;
 use crate::prelude::*;
-use crate::screen::Screen;
-use crate::screen::fade::fade_out;
-use crate::screen::title::TitleAssets;
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(StateFlush, Menu::Intro.on_enter(spawn_intro_menu));
@@ -31,7 +28,7 @@ fn spawn_intro_menu(mut commands: Commands, menu_root: Res<MenuRoot>) {
             ),
             widget::row_of_buttons(children![
                 widget::button("Decline", go_back),
-                widget::button("Pursue", start_game)
+                widget::button("Pursue", open_ship_select)
             ]),
         ]));
 }
@@ -41,18 +38,7 @@ fn go_back(trigger: Trigger<Pointer<Click>>, mut menu: ResMut<NextStateStack<Men
     menu.pop();
 }
 
-fn start_game(
-    trigger: Trigger<Pointer<Click>>,
-    mut commands: Commands,
-    title_assets: Res<TitleAssets>,
-    progress: Res<ProgressTracker<BevyState<Screen>>>,
-    mut menu: ResMut<NextStateStack<Menu>>,
-) {
+fn open_ship_select(trigger: Trigger<Pointer<Click>>, mut menu: ResMut<NextStateStack<Menu>>) {
     rq!(matches!(trigger.event.button, PointerButton::Primary));
-    let Progress { done, total } = progress.get_global_combined_progress();
-    if done >= total {
-        commands.spawn(fade_out(&title_assets, Screen::Gameplay));
-    } else {
-        menu.push(Menu::Loading);
-    }
+    menu.push(Menu::ShipSelect);
 }
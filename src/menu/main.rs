@@ -1,6 +1,15 @@
+use crate::deck::SelectedDeck;
+use crate::endless::EndlessMode;
 use crate::menu::Menu;
 use crate::menu::MenuRoot;
 use crate::prelude::*;
+use crate::replay::Playback;
+use crate::replay::Replay;
+use crate::save::Resume;
+use crate::save::SaveGame;
+use crate::screen::Screen;
+use crate::screen::fade::fade_out;
+use crate::screen::title::TitleAssets;
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(StateFlush, Menu::Main.on_enter(spawn_main_menu));
@@ -10,11 +19,11 @@ fn spawn_main_menu(mut commands: Commands, menu_root: Res<MenuRoot>) {
     commands.entity(menu_root.ui).with_child((
         Name::new("MainMenuContainer"),
         Node::ROW.full_size(),
-        children![side_panel(), title()],
+        children![side_panel(SaveGame::exists(), Replay::exists()), title()],
     ));
 }
 
-fn side_panel() -> impl Bundle {
+fn side_panel(can_continue: bool, can_replay: bool) -> impl Bundle {
     (
         Name::new("SidePanel"),
         Node {
@@ -32,7 +41,31 @@ fn side_panel() -> impl Bundle {
             blur_radius: Vw(2.5),
         }),
         children![widget::column_of_buttons(children![
+            (
+                widget::button("Continue", continue_game),
+                Patch(move |entity| {
+                    r!(entity.get_mut::<InteractionDisabled>()).0 = !can_continue;
+                }),
+            ),
             widget::button("Play", open_intro),
+            (
+                widget::button("Endless", open_endless_intro),
+                Tooltip::fixed(
+                    Anchor::CenterRight,
+                    parse_rich(
+                        "[b]Endless mode[r]\n\n\
+                        Keep going past the last star into procedurally generated space.\n\
+                        How deep can you reach?",
+                    ),
+                ),
+            ),
+            (
+                widget::button("Replay", watch_replay),
+                Patch(move |entity| {
+                    r!(entity.get_mut::<InteractionDisabled>()).0 = !can_replay;
+                }),
+            ),
+            widget::button("Records", open_records),
             widget::button("Settings", open_settings),
             widget::button("Credits", open_credits),
             (
@@ -57,11 +90,71 @@ fn title() -> impl Bundle {
     )
 }
 
-fn open_intro(trigger: Trigger<Pointer<Click>>, mut menu: ResMut<NextStateStack<Menu>>) {
+fn continue_game(
+    trigger: Trigger<Pointer<Click>>,
+    mut commands: Commands,
+    title_assets: Res<TitleAssets>,
+    progress: Res<ProgressTracker<BevyState<Screen>>>,
+    mut menu: ResMut<NextStateStack<Menu>>,
+) {
     rq!(matches!(trigger.event.button, PointerButton::Primary));
+    let save = rq!(SaveGame::load());
+    commands.insert_resource(Resume(save));
+
+    let Progress { done, total } = progress.get_global_combined_progress();
+    if done >= total {
+        commands.spawn(fade_out(&title_assets, Screen::Gameplay));
+    } else {
+        menu.push(Menu::Loading);
+    }
+}
+
+fn watch_replay(
+    trigger: Trigger<Pointer<Click>>,
+    mut commands: Commands,
+    title_assets: Res<TitleAssets>,
+    progress: Res<ProgressTracker<BevyState<Screen>>>,
+    mut menu: ResMut<NextStateStack<Menu>>,
+) {
+    rq!(matches!(trigger.event.button, PointerButton::Primary));
+    let replay = rq!(Replay::load());
+    commands.insert_resource(SelectedDeck(replay.deck));
+    commands.insert_resource(EndlessMode(replay.endless));
+    commands.insert_resource(Playback::new(replay));
+
+    let Progress { done, total } = progress.get_global_combined_progress();
+    if done >= total {
+        commands.spawn(fade_out(&title_assets, Screen::Gameplay));
+    } else {
+        menu.push(Menu::Loading);
+    }
+}
+
+fn open_intro(
+    trigger: Trigger<Pointer<Click>>,
+    mut endless: ResMut<EndlessMode>,
+    mut menu: ResMut<NextStateStack<Menu>>,
+) {
+    rq!(matches!(trigger.event.button, PointerButton::Primary));
+    endless.0 = false;
     menu.push(Menu::Intro);
 }
 
+fn open_endless_intro(
+    trigger: Trigger<Pointer<Click>>,
+    mut endless: ResMut<EndlessMode>,
+    mut menu: ResMut<NextStateStack<Menu>>,
+) {
+    rq!(matches!(trigger.event.button, PointerButton::Primary));
+    endless.0 = true;
+    menu.push(Menu::Intro);
+}
+
+fn open_records(trigger: Trigger<Pointer<Click>>, mut menu: ResMut<NextStateStack<Menu>>) {
+    rq!(matches!(trigger.event.button, PointerButton::Primary));
+    menu.push(Menu::Records);
+}
+
 fn open_settings(trigger: Trigger<Pointer<Click>>, mut menu: ResMut<NextStateStack<Menu>>) {
     rq!(matches!(trigger.event.button, PointerButton::Primary));
     menu.push(Menu::Settings);
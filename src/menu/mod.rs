@@ -1,12 +1,16 @@
 mod credits;
+mod deck;
 mod defeat;
 mod help;
 mod intro;
 mod loading;
 mod main;
 mod pause;
+mod records;
 mod settings;
-mod upgrade;
+mod ship_select;
+pub mod shop;
+pub mod upgrade;
 mod victory;
 
 use crate::prelude::*;
@@ -50,12 +54,16 @@ impl FromWorld for MenuRoot {
 pub enum Menu {
     Main,
     Intro,
+    ShipSelect,
     Settings,
     Credits,
+    Records,
     Loading,
     Help,
+    Deck,
     Pause,
     Upgrade,
+    Shop,
     Defeat,
     Victory,
 }
@@ -74,12 +82,16 @@ impl Configure for Menu {
         app.add_plugins((
             main::plugin,
             intro::plugin,
+            ship_select::plugin,
             settings::plugin,
             credits::plugin,
+            records::plugin,
             loading::plugin,
             help::plugin,
+            deck::plugin,
             pause::plugin,
             upgrade::plugin,
+            shop::plugin,
             defeat::plugin,
             victory::plugin,
         ));
@@ -2,6 +2,7 @@ use crate::level::Level;
 use crate::menu::Menu;
 use crate::menu::MenuRoot;
 use crate::prelude::*;
+use crate::rng::GameRng;
 use crate::screen::Screen;
 use crate::screen::fade::fade_out;
 use crate::screen::title::TitleAssets;
@@ -10,7 +11,7 @@ pub(super) fn plugin(app: &mut App) {
     app.add_systems(StateFlush, Menu::Pause.on_enter(spawn_pause_menu));
 }
 
-fn spawn_pause_menu(mut commands: Commands, menu_root: Res<MenuRoot>) {
+fn spawn_pause_menu(mut commands: Commands, menu_root: Res<MenuRoot>, game_rng: Res<GameRng>) {
     commands
         .entity(menu_root.ui)
         .with_child(widget::body(children![
@@ -18,9 +19,11 @@ fn spawn_pause_menu(mut commands: Commands, menu_root: Res<MenuRoot>) {
             widget::column_of_buttons(children![
                 widget::wide_button("Continue", close_menu),
                 widget::wide_button("Settings", open_settings),
+                widget::wide_button("View deck", open_deck),
                 widget::wide_button("Retry star", restart_level),
                 widget::wide_button("End pursuit", quit_to_title),
-            ])
+            ]),
+            widget::small_label(format!("[b]Seed:[r] {}", game_rng.seed)),
         ]));
 }
 
@@ -39,6 +42,11 @@ fn open_settings(trigger: Trigger<Pointer<Click>>, mut menu: ResMut<NextStateSta
     menu.push(Menu::Settings);
 }
 
+fn open_deck(trigger: Trigger<Pointer<Click>>, mut menu: ResMut<NextStateStack<Menu>>) {
+    rq!(matches!(trigger.event.button, PointerButton::Primary));
+    menu.push(Menu::Deck);
+}
+
 fn quit_to_title(
     trigger: Trigger<Pointer<Click>>,
     mut commands: Commands,
@@ -0,0 +1,265 @@
+use crate::history::RunHistory;
+use crate::history::RunRecord;
+use crate::history::format_duration;
+use crate::menu::Menu;
+use crate::menu::MenuRoot;
+use crate::menu::upgrade::UpgradeConfig;
+use crate::module::ModuleConfig;
+use crate::prelude::*;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(StateFlush, Menu::Records.on_enter(spawn_records_menu));
+
+    app.configure::<(SelectedRun, RunSelector, RunDetails)>();
+}
+
+fn spawn_records_menu(
+    mut commands: Commands,
+    menu_root: Res<MenuRoot>,
+    history: Res<RunHistory>,
+    mut selected_run: ResMut<SelectedRun>,
+) {
+    selected_run.0 = history.runs.len().saturating_sub(1);
+
+    commands
+        .entity(menu_root.ui)
+        .with_child(widget::popup(children![
+            widget::header("[b]Records"),
+            career_grid(&history),
+            widget::selector(RunSelector, previous_run, next_run),
+            (
+                widget::small_label(""),
+                RunDetails,
+                Node {
+                    margin: UiRect::top(Vw(1.4)),
+                    min_height: Vw(9.0),
+                    ..default()
+                },
+            ),
+            widget::row_of_buttons(children![widget::wide_button("Back", go_back)]),
+        ]));
+}
+
+fn go_back(trigger: Trigger<Pointer<Click>>, mut menu: ResMut<NextStateStack<Menu>>) {
+    rq!(matches!(trigger.event.button, PointerButton::Primary));
+    menu.pop();
+}
+
+fn career_grid(history: &RunHistory) -> impl Bundle {
+    let totals = history.totals();
+    let stars = history.runs.iter().map(|x| x.stars).sum::<usize>();
+    let fastest_victory = history
+        .fastest_victory()
+        .map_or("-".to_string(), format_duration);
+
+    (
+        Name::new("CareerGrid"),
+        Node {
+            display: Display::Grid,
+            margin: UiRect::top(Vw(2.0)).with_bottom(Vw(3.0)),
+            row_gap: Vw(1.0),
+            column_gap: Vw(2.0),
+            grid_template_columns: vec![
+                RepeatedGridTrack::auto(1),
+                RepeatedGridTrack::flex(1, 1.0),
+                RepeatedGridTrack::auto(1),
+                RepeatedGridTrack::flex(1, 1.0),
+            ],
+            ..default()
+        },
+        GridAlignment::columns([
+            JustifySelf::End,
+            JustifySelf::Start,
+            JustifySelf::End,
+            JustifySelf::Start,
+        ]),
+        children![
+            widget::small_label(history.runs.len().to_string()),
+            widget::small_label("[b]runs played"),
+            widget::small_label(history.highest_flux().to_string()),
+            widget::small_label("[b]highest flux"),
+            widget::small_label(history.victories().to_string()),
+            widget::small_label("[b]victories"),
+            widget::small_label(history.highest_damage().to_string()),
+            widget::small_label("[b]max damage"),
+            widget::small_label(stars.to_string()),
+            widget::small_label("[b]stars defended"),
+            widget::small_label(fastest_victory),
+            widget::small_label("[b]fastest victory"),
+            widget::small_label(totals.damage_given.to_string()),
+            widget::small_label("[b]damage given"),
+            widget::small_label(format_duration(totals.duration)),
+            widget::small_label("[b]time played"),
+            widget::small_label(history.deepest_star().to_string()),
+            widget::small_label("[b]deepest star"),
+        ],
+    )
+}
+
+/// The index of the run shown in the run browser.
+#[derive(Resource, Reflect, Default, Debug)]
+#[reflect(Resource)]
+struct SelectedRun(usize);
+
+impl Configure for SelectedRun {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.init_resource::<Self>();
+    }
+}
+
+fn previous_run(trigger: Trigger<Pointer<Click>>, mut selected_run: ResMut<SelectedRun>) {
+    rq!(matches!(trigger.event.button, PointerButton::Primary));
+    selected_run.0 = selected_run.0.saturating_sub(1);
+}
+
+fn next_run(
+    trigger: Trigger<Pointer<Click>>,
+    history: Res<RunHistory>,
+    mut selected_run: ResMut<SelectedRun>,
+) {
+    rq!(matches!(trigger.event.button, PointerButton::Primary));
+    selected_run.0 = (selected_run.0 + 1).min(history.runs.len().saturating_sub(1));
+}
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct RunSelector;
+
+impl Configure for RunSelector {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            Update,
+            Menu::Records.on_update(update_run_selector.in_set(UpdateSystems::Update)),
+        );
+    }
+}
+
+fn update_run_selector(
+    history: Res<RunHistory>,
+    selected_run: Res<SelectedRun>,
+    selector_query: Query<Entity, With<RunSelector>>,
+    children_query: Query<&Children>,
+    mut text_query: Query<&mut RichText>,
+    mut disabled_query: Query<&mut InteractionDisabled>,
+) {
+    let count = history.runs.len();
+    for entity in &selector_query {
+        let children = c!(children_query.get(entity))
+            .into_iter()
+            .collect::<Vec<_>>();
+
+        let left = **c!(children.first());
+        c!(disabled_query.get_mut(left)).0 = selected_run.0 == 0;
+
+        let mid = **c!(children.get(1));
+        let mid_children = c!(children_query.get(mid));
+        let label = *c!(mid_children.first());
+        c!(text_query.get_mut(label)).sections = parse_rich(if count == 0 {
+            "No runs yet".to_string()
+        } else {
+            format!("Run {} of {count}", selected_run.0 + 1)
+        });
+
+        let right = **c!(children.get(2));
+        c!(disabled_query.get_mut(right)).0 = selected_run.0 + 1 >= count;
+    }
+}
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct RunDetails;
+
+impl Configure for RunDetails {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            Update,
+            Menu::Records.on_update(
+                update_run_details
+                    .in_set(UpdateSystems::Update)
+                    .run_if(resource_changed::<SelectedRun>.or(any_match_filter::<Added<Self>>)),
+            ),
+        );
+    }
+}
+
+fn update_run_details(
+    history: Res<RunHistory>,
+    selected_run: Res<SelectedRun>,
+    module_config: ConfigRef<ModuleConfig>,
+    upgrade_config: ConfigRef<UpgradeConfig>,
+    mut details_query: Query<&mut RichText, With<RunDetails>>,
+) {
+    let module_config = r!(module_config.get());
+    let upgrade_config = r!(upgrade_config.get());
+    let details = history
+        .runs
+        .get(selected_run.0)
+        .map(|run| run_description(run, module_config, upgrade_config))
+        .unwrap_or_default();
+
+    for mut text in &mut details_query {
+        text.sections = parse_rich(&details);
+    }
+}
+
+fn run_description(
+    run: &RunRecord,
+    module_config: &ModuleConfig,
+    upgrade_config: &UpgradeConfig,
+) -> String {
+    let outcome = match (run.victory, run.endless) {
+        (true, _) => "Victory",
+        (false, true) => "Endless defeat",
+        (false, false) => "Defeat",
+    };
+
+    let mut upgrades = run
+        .upgrades
+        .taken
+        .iter()
+        .map(|(key, count)| {
+            let name = upgrade_config
+                .upgrades
+                .get(key)
+                .map_or(key.as_str(), |x| x.name.as_str());
+            format!("{name} x{count}")
+        })
+        .collect::<Vec<_>>();
+    upgrades.sort();
+    let upgrades = if upgrades.is_empty() {
+        "none".to_string()
+    } else {
+        upgrades.join(", ")
+    };
+
+    let mut modules = HashMap::<String, usize>::default();
+    for module in &run.modules {
+        *modules
+            .entry(module_config.action(&module.effect).effect_name.clone())
+            .or_default() += 1;
+    }
+    let mut modules = modules
+        .into_iter()
+        .map(|(name, count)| format!("{name} x{count}"))
+        .collect::<Vec<_>>();
+    modules.sort();
+
+    format!(
+        "[b]{outcome}[r] aboard {} after {} star{} in {} (seed {})\n\
+        {} rounds, {} damage given, {} damage taken\n\
+        [b]Upgrades:[r] {upgrades}\n\
+        [b]Modules:[r] {}",
+        run.ship,
+        run.stars,
+        if run.stars == 1 { "" } else { "s" },
+        format_duration(run.stats.duration),
+        run.seed,
+        run.stats.total_rounds,
+        run.stats.damage_given,
+        run.stats.damage_taken,
+        modules.join(", "),
+    )
+}
@@ -0,0 +1,220 @@
+use crate::deck::DeckConfig;
+use crate::deck::PlayerDeck;
+use crate::deck::SelectedDeck;
+use crate::menu::Menu;
+use crate::menu::MenuRoot;
+use crate::module::ModuleConfig;
+use crate::module::ModuleStatus;
+use crate::prelude::*;
+use crate::screen::Screen;
+use crate::screen::fade::fade_out;
+use crate::screen::title::TitleAssets;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        StateFlush,
+        Menu::ShipSelect.on_enter(spawn_ship_select_menu),
+    );
+
+    app.configure::<(ShipSelector, ShipPreview, ShipDetails)>();
+}
+
+fn spawn_ship_select_menu(mut commands: Commands, menu_root: Res<MenuRoot>) {
+    commands
+        .entity(menu_root.ui)
+        .with_child(widget::popup(children![
+            widget::header("[b]Choose your ship"),
+            widget::selector(ShipSelector, previous_ship, next_ship),
+            (
+                Name::new("ShipInfo"),
+                Node {
+                    margin: UiRect::top(Vw(2.0)).with_bottom(Vw(2.5)),
+                    column_gap: Vw(4.0),
+                    ..Node::ROW.center()
+                },
+                children![
+                    (
+                        Name::new("ShipPreview"),
+                        ShipPreview,
+                        ImageNode::default(),
+                        Node {
+                            width: Vw(12.0),
+                            ..default()
+                        },
+                    ),
+                    (widget::small_label(""), ShipDetails),
+                ],
+            ),
+            widget::row_of_buttons(children![
+                widget::button("Back", go_back),
+                widget::button("Launch", start_game),
+            ]),
+        ]));
+}
+
+fn go_back(trigger: Trigger<Pointer<Click>>, mut menu: ResMut<NextStateStack<Menu>>) {
+    rq!(matches!(trigger.event.button, PointerButton::Primary));
+    menu.pop();
+}
+
+fn start_game(
+    trigger: Trigger<Pointer<Click>>,
+    mut commands: Commands,
+    title_assets: Res<TitleAssets>,
+    progress: Res<ProgressTracker<BevyState<Screen>>>,
+    mut menu: ResMut<NextStateStack<Menu>>,
+) {
+    rq!(matches!(trigger.event.button, PointerButton::Primary));
+    let Progress { done, total } = progress.get_global_combined_progress();
+    if done >= total {
+        commands.spawn(fade_out(&title_assets, Screen::Gameplay));
+    } else {
+        menu.push(Menu::Loading);
+    }
+}
+
+fn previous_ship(trigger: Trigger<Pointer<Click>>, mut selected_deck: ResMut<SelectedDeck>) {
+    rq!(matches!(trigger.event.button, PointerButton::Primary));
+    selected_deck.0 = selected_deck.0.saturating_sub(1);
+}
+
+fn next_ship(
+    trigger: Trigger<Pointer<Click>>,
+    deck_config: ConfigRef<DeckConfig>,
+    mut selected_deck: ResMut<SelectedDeck>,
+) {
+    rq!(matches!(trigger.event.button, PointerButton::Primary));
+    let deck_count = r!(deck_config.get()).player_decks.len();
+    selected_deck.0 = (selected_deck.0 + 1).min(deck_count.saturating_sub(1));
+}
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct ShipSelector;
+
+impl Configure for ShipSelector {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            Update,
+            Menu::ShipSelect.on_update(update_ship_selector.in_set(UpdateSystems::Update)),
+        );
+    }
+}
+
+fn update_ship_selector(
+    deck_config: ConfigRef<DeckConfig>,
+    selected_deck: Res<SelectedDeck>,
+    selector_query: Query<Entity, With<ShipSelector>>,
+    children_query: Query<&Children>,
+    mut text_query: Query<&mut RichText>,
+    mut disabled_query: Query<&mut InteractionDisabled>,
+) {
+    let deck_config = r!(deck_config.get());
+    let deck_count = deck_config.player_decks.len();
+    let name = deck_config
+        .player_decks
+        .get(selected_deck.0)
+        .map(|x| x.name.as_str())
+        .unwrap_or_default();
+
+    for entity in &selector_query {
+        let children = c!(children_query.get(entity))
+            .into_iter()
+            .collect::<Vec<_>>();
+
+        let left = **c!(children.first());
+        c!(disabled_query.get_mut(left)).0 = selected_deck.0 == 0;
+
+        let mid = **c!(children.get(1));
+        let mid_children = c!(children_query.get(mid));
+        let label = *c!(mid_children.first());
+        c!(text_query.get_mut(label)).sections = parse_rich(format!("[b]{name}"));
+
+        let right = **c!(children.get(2));
+        c!(disabled_query.get_mut(right)).0 = selected_deck.0 + 1 >= deck_count;
+    }
+}
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct ShipPreview;
+
+impl Configure for ShipPreview {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            Update,
+            Menu::ShipSelect.on_update(update_ship_preview.in_set(UpdateSystems::Update)),
+        );
+    }
+}
+
+fn update_ship_preview(
+    deck_config: ConfigRef<DeckConfig>,
+    selected_deck: Res<SelectedDeck>,
+    mut preview_query: Query<&mut ImageNode, With<ShipPreview>>,
+) {
+    let deck_config = r!(deck_config.get());
+    let player_deck = r!(deck_config.player_decks.get(selected_deck.0));
+    let image = deck_config.sprite(&player_deck.sprite);
+
+    for mut image_node in &mut preview_query {
+        cq!(image_node.image != image);
+        image_node.image = image.clone();
+    }
+}
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct ShipDetails;
+
+impl Configure for ShipDetails {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            Update,
+            Menu::ShipSelect.on_update(update_ship_details.in_set(UpdateSystems::Update)),
+        );
+    }
+}
+
+fn update_ship_details(
+    deck_config: ConfigRef<DeckConfig>,
+    module_config: ConfigRef<ModuleConfig>,
+    selected_deck: Res<SelectedDeck>,
+    mut details_query: Query<&mut RichText, With<ShipDetails>>,
+) {
+    let deck_config = r!(deck_config.get());
+    let module_config = r!(module_config.get());
+    let player_deck = r!(deck_config.player_decks.get(selected_deck.0));
+    let details = ship_description(player_deck, module_config);
+
+    for mut text in &mut details_query {
+        text.sections = parse_rich(&details);
+    }
+}
+
+fn ship_description(player_deck: &PlayerDeck, module_config: &ModuleConfig) -> String {
+    let modules = player_deck
+        .storage
+        .iter()
+        .chain(&player_deck.hand)
+        .chain(&player_deck.reactor)
+        .filter(|x| !matches!(x.status, ModuleStatus::SlotEmpty))
+        .map(|x| x.short_description(module_config))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    format!(
+        "[b]Max health:[r] {}\n\
+        [b]Heat capacity:[r] {}\n\
+        [b]Hand size:[r] {}\n\
+        [b]Reactor slots:[r] {}\n\n\
+        {modules}",
+        player_deck.max_health,
+        player_deck.heat_capacity,
+        player_deck.hand_size,
+        player_deck.reactor.len(),
+    )
+}
@@ -0,0 +1,755 @@
+use crate::animation::offset::NodeOffset;
+use crate::combat::health::Health;
+use crate::deck::PlayerDeck;
+use crate::hud::module::module;
+use crate::level::Level;
+use crate::menu::Menu;
+use crate::menu::MenuRoot;
+use crate::menu::upgrade::Upgrade;
+use crate::menu::upgrade::UpgradeConfig;
+use crate::menu::upgrade::UpgradeEffect;
+use crate::menu::upgrade::UpgradeHistory;
+use crate::menu::upgrade::curve;
+use crate::module::Module;
+use crate::module::ModuleConfig;
+use crate::prelude::*;
+use crate::projectile::ProjectileConfig;
+use crate::relic::RelicConfig;
+use crate::rng::GameRng;
+use crate::save::Resume;
+use crate::screen::gameplay::GameplayAssets;
+use crate::ship::PlayerShip;
+use crate::stats::Stats;
+
+pub(super) fn plugin(app: &mut App) {
+    app.configure::<(
+        ConfigHandle<ShopConfig>,
+        Salvage,
+        ShopStock,
+        SalvageLabel,
+        ShopOffers,
+        ShopOfferSelector,
+        ScrapRow,
+        ScrapSelector,
+        RepairButton,
+        BuyShopOffer,
+        ScrapModule,
+        RepairHull,
+        LeaveShop,
+    )>();
+
+    app.add_systems(
+        StateFlush,
+        Menu::Shop.on_enter(
+            (
+                restock_shop.run_if(not(resource_exists::<Resume>)),
+                spawn_shop_menu,
+            )
+                .chain(),
+        ),
+    );
+}
+
+#[derive(Asset, Reflect, Serialize, Deserialize, Default)]
+#[serde(deny_unknown_fields, default)]
+pub struct ShopConfig {
+    /// Salvage earned for each point of damage given to the enemy.
+    pub salvage_per_damage: f32,
+    /// Salvage earned for clearing each level.
+    pub clear_salvage: Vec<f32>,
+    /// Whether missing hull health carries over to the next level and can be repaired.
+    pub carry_hull_damage: bool,
+    /// The fraction of missing hull health carried over to the next level.
+    pub hull_damage_kept: f32,
+    /// Hull health restored by each repair.
+    pub repair_amount: f32,
+    pub repair_price: usize,
+    /// The price to scrap the first module in a run.
+    pub scrap_price: usize,
+    /// Added to the scrap price for each module scrapped in the run.
+    pub scrap_price_increase: usize,
+    /// The number of modules for sale.
+    pub module_count: usize,
+    pub modules: Vec<ShopModule>,
+    /// The number of upgrades for sale, drawn by their weight in the upgrade config.
+    pub upgrade_count: usize,
+    /// Upgrades that can be sold, and their prices.
+    pub upgrades: Vec<(String, usize)>,
+}
+
+impl Config for ShopConfig {
+    const FILE: &'static str = "shop.ron";
+}
+
+impl ShopConfig {
+    /// The salvage earned for clearing a level after giving the enemy this much damage.
+    pub fn earnings(&self, level: usize, damage: f32) -> usize {
+        (curve(&self.clear_salvage, level) + self.salvage_per_damage * damage.max(0.0)).round()
+            as usize
+    }
+
+    /// The missing hull health to carry over to the next level.
+    pub fn hull_damage(&self, max_health: f32, health: f32) -> f32 {
+        rq!(self.carry_hull_damage);
+        ((max_health - health) * self.hull_damage_kept).max(0.0)
+    }
+
+    fn scrap_price(&self, salvage: &Salvage) -> usize {
+        self.scrap_price + self.scrap_price_increase * salvage.scrapped
+    }
+
+    fn generate_stock(
+        &self,
+        rng: &mut impl Rng,
+        upgrade_config: &UpgradeConfig,
+        upgrade_history: &UpgradeHistory,
+        player_deck: &PlayerDeck,
+        level: usize,
+    ) -> Vec<ShopOffer> {
+        let mut offers = vec![];
+
+        for _ in 0..self.module_count {
+            let Ok(module) = self
+                .modules
+                .choose_weighted(rng, |x| curve(&x.weight, level))
+            else {
+                break;
+            };
+            offers.push(ShopOffer {
+                item: ShopItem::Module(Module::new(&module.condition, &module.effect)),
+                price: module.price,
+                sold: false,
+            });
+        }
+
+        let mut upgrades = self.upgrades.clone();
+        for _ in 0..self.upgrade_count {
+            let Ok((key, price)) = upgrades
+                .choose_weighted(rng, |(key, _)| {
+                    upgrade_config.weight(key, level, upgrade_history)
+                })
+                .cloned()
+            else {
+                break;
+            };
+            // Don't offer the same upgrade twice.
+            upgrades.retain(|(x, _)| *x != key);
+            let upgrade = Upgrade::generate(rng, upgrade_config, player_deck, &key);
+            // Skip relics if the player already has every relic in the pool.
+            if upgrade.relic.is_none()
+                && matches!(
+                    upgrade_config.upgrades.get(&key).map(|x| &x.effect),
+                    Some(UpgradeEffect::Relic(_)),
+                )
+            {
+                continue;
+            }
+            offers.push(ShopOffer {
+                item: ShopItem::Upgrade(upgrade),
+                price,
+                sold: false,
+            });
+        }
+
+        offers
+    }
+}
+
+/// A module that can be sold in the shop.
+#[derive(Reflect, Serialize, Deserialize, Default, Debug)]
+#[serde(deny_unknown_fields, default)]
+pub struct ShopModule {
+    pub condition: String,
+    pub effect: String,
+    pub price: usize,
+    /// The weight at each level.
+    pub weight: Vec<f32>,
+}
+
+/// The currency spent in the shop, earned by damaging enemies and clearing levels.
+#[derive(Resource, Reflect, Serialize, Deserialize, Default, Clone, Debug)]
+#[reflect(Resource)]
+#[serde(default)]
+pub struct Salvage {
+    pub amount: usize,
+    /// The total damage given that salvage has already been earned for.
+    pub claimed_damage: f32,
+    /// The number of modules scrapped in the run.
+    pub scrapped: usize,
+}
+
+impl Configure for Salvage {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.init_resource::<Self>();
+        app.add_systems(
+            StateFlush,
+            (
+                Level(0).on_enter(reset_salvage.run_if(not(resource_exists::<Resume>))),
+                Level::ANY.on_enter(roll_back_retried_damage),
+            ),
+        );
+    }
+}
+
+fn reset_salvage(mut salvage: ResMut<Salvage>) {
+    *salvage = default();
+}
+
+/// Forget the damage given in a failed attempt at a level, so retrying doesn't earn salvage for it.
+fn roll_back_retried_damage(
+    current_level: CurrentRef<Level>,
+    next_level: NextRef<Level>,
+    salvage: Res<Salvage>,
+    mut stats: ResMut<Stats>,
+) {
+    let level = rq!(current_level.get());
+    rq!(next_level.get() == Some(level));
+    stats.damage_given = salvage.claimed_damage;
+}
+
+/// The offers in the current shop.
+#[derive(Resource, Reflect, Serialize, Deserialize, Default, Clone, Debug)]
+#[reflect(Resource)]
+#[serde(default)]
+pub struct ShopStock {
+    pub offers: Vec<ShopOffer>,
+    /// The salvage earned on the way into the shop.
+    pub earned: usize,
+}
+
+impl Configure for ShopStock {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.init_resource::<Self>();
+    }
+}
+
+#[derive(Reflect, Serialize, Deserialize, Clone, Debug)]
+pub struct ShopOffer {
+    pub item: ShopItem,
+    pub price: usize,
+    pub sold: bool,
+}
+
+#[derive(Reflect, Serialize, Deserialize, Clone, Debug)]
+pub enum ShopItem {
+    Module(Module),
+    Upgrade(Upgrade),
+}
+
+pub fn restock_shop(
+    shop_config: ConfigRef<ShopConfig>,
+    upgrade_config: ConfigRef<UpgradeConfig>,
+    level: CurrentRef<Level>,
+    stats: Res<Stats>,
+    upgrade_history: Res<UpgradeHistory>,
+    health_query: Query<&Health, With<PlayerShip>>,
+    mut salvage: ResMut<Salvage>,
+    mut player_deck: ResMut<PlayerDeck>,
+    mut shop_stock: ResMut<ShopStock>,
+    mut game_rng: ResMut<GameRng>,
+) {
+    let shop_config = r!(shop_config.get());
+    let upgrade_config = r!(upgrade_config.get());
+    let level = r!(level.get()).0;
+
+    // Earn salvage from the level.
+    let earned = shop_config.earnings(level, stats.damage_given - salvage.claimed_damage);
+    salvage.amount += earned;
+    salvage.claimed_damage = stats.damage_given;
+
+    // Carry hull damage over to the next level.
+    if let Ok(health) = health_query.single() {
+        player_deck.hull_damage = shop_config.hull_damage(health.max, health.current);
+    }
+
+    // Return modules to storage so any of them can be scrapped.
+    player_deck.reset();
+
+    *shop_stock = ShopStock {
+        offers: shop_config.generate_stock(
+            &mut game_rng.upgrade,
+            upgrade_config,
+            &upgrade_history,
+            &player_deck,
+            level,
+        ),
+        earned,
+    };
+}
+
+fn spawn_shop_menu(
+    mut commands: Commands,
+    menu_root: Res<MenuRoot>,
+    shop_config: ConfigRef<ShopConfig>,
+    shop_stock: Res<ShopStock>,
+) {
+    let shop_config = r!(shop_config.get());
+    let carry_hull_damage = shop_config.carry_hull_damage;
+
+    commands
+        .entity(menu_root.ui)
+        .with_child(widget::popup(children![
+            widget::header("[b]Salvage yard"),
+            widget::label(format!(
+                "You recovered {} salvage from the chase.",
+                shop_stock.earned,
+            )),
+            (widget::small_label(""), SalvageLabel),
+            (
+                Name::new("ShopOffers"),
+                ShopOffers,
+                Node {
+                    margin: UiRect::top(Vw(2.0)).with_bottom(Vw(2.0)),
+                    column_gap: Vw(1.0),
+                    ..Node::ROW.center()
+                },
+            ),
+            (
+                Name::new("ScrapRow"),
+                ScrapRow,
+                Node {
+                    flex_wrap: FlexWrap::Wrap,
+                    row_gap: Vw(0.5),
+                    column_gap: Vw(0.5),
+                    ..Node::ROW.center()
+                },
+            ),
+            widget::row_of_buttons(children![
+                (
+                    RepairButton,
+                    widget::button("Repair hull", repair_hull),
+                    Patch(move |entity| {
+                        rq!(!carry_hull_damage);
+                        r!(entity.get_mut::<Node>()).display = Display::None;
+                    }),
+                    Tooltip::fixed(
+                        Anchor::TopCenter,
+                        parse_rich(format!(
+                            "Repair {} hull damage for {} salvage.",
+                            shop_config.repair_amount, shop_config.repair_price,
+                        )),
+                    ),
+                ),
+                widget::button("Pursue", leave_shop),
+            ]),
+        ]));
+}
+
+fn repair_hull(
+    trigger: Trigger<Pointer<Click>>,
+    mut commands: Commands,
+    button_query: Query<&InteractionDisabled, With<Button>>,
+) {
+    rq!(matches!(trigger.event.button, PointerButton::Primary));
+    let target = r!(trigger.get_target());
+    let disabled = r!(button_query.get(target));
+    rq!(!disabled.0);
+    commands.trigger(RepairHull);
+}
+
+fn leave_shop(trigger: Trigger<Pointer<Click>>, mut commands: Commands) {
+    rq!(matches!(trigger.event.button, PointerButton::Primary));
+    commands.trigger(LeaveShop);
+}
+
+/// Shows the player's salvage.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct SalvageLabel;
+
+impl Configure for SalvageLabel {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            Update,
+            Menu::Shop.on_update(
+                sync_salvage_label
+                    .in_set(UpdateSystems::SyncLate)
+                    .run_if(resource_changed::<Salvage>.or(any_match_filter::<Added<Self>>)),
+            ),
+        );
+    }
+}
+
+fn sync_salvage_label(
+    salvage: Res<Salvage>,
+    mut text_query: Query<&mut RichText, With<SalvageLabel>>,
+) {
+    for mut text in &mut text_query {
+        text.sections = parse_rich(format!("[b]Salvage:[r] {}", salvage.amount));
+    }
+}
+
+/// Lists the offers in the [`ShopStock`].
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct ShopOffers;
+
+impl Configure for ShopOffers {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            Update,
+            Menu::Shop.on_update(
+                sync_shop_offers.in_set(UpdateSystems::SyncLate).run_if(
+                    resource_changed::<ShopStock>
+                        .or(resource_changed::<Salvage>)
+                        .or(any_match_filter::<Added<Self>>),
+                ),
+            ),
+        );
+    }
+}
+
+fn sync_shop_offers(
+    mut commands: Commands,
+    game_assets: Res<GameplayAssets>,
+    module_config: ConfigRef<ModuleConfig>,
+    projectile_config: ConfigRef<ProjectileConfig>,
+    upgrade_config: ConfigRef<UpgradeConfig>,
+    relic_config: ConfigRef<RelicConfig>,
+    shop_stock: Res<ShopStock>,
+    salvage: Res<Salvage>,
+    player_deck: Res<PlayerDeck>,
+    offers_query: Query<Entity, With<ShopOffers>>,
+) {
+    let module_config = r!(module_config.get());
+    let projectile_config = r!(projectile_config.get());
+    let upgrade_config = r!(upgrade_config.get());
+    let relic_config = r!(relic_config.get());
+
+    for entity in &offers_query {
+        commands
+            .entity(entity)
+            .despawn_related::<Children>()
+            .with_children(|parent| {
+                for (idx, offer) in shop_stock.offers.iter().enumerate() {
+                    let description = match &offer.item {
+                        ShopItem::Module(x) => x.description(
+                            module_config,
+                            projectile_config,
+                            player_deck.heat_capacity,
+                        ),
+                        ShopItem::Upgrade(x) => {
+                            x.description(upgrade_config, module_config, relic_config)
+                        },
+                    };
+                    let price = if offer.sold {
+                        "[b]Sold".to_string()
+                    } else {
+                        format!("[b]{}", offer.price)
+                    };
+
+                    let mut selector = parent.spawn((
+                        Name::new("ShopOfferSelector"),
+                        ShopOfferSelector(idx),
+                        Button,
+                        Node {
+                            row_gap: Vw(0.5),
+                            ..Node::COLUMN.center()
+                        },
+                        Tooltip::fixed(
+                            Anchor::BottomCenter,
+                            RichText::from_sections(parse_rich(description))
+                                .with_justify(JustifyText::Center),
+                        ),
+                        Previous::<Interaction>::default(),
+                        InteractionGlassSfx,
+                        InteractionDisabled(offer.sold || offer.price > salvage.amount),
+                        NodeOffset::default(),
+                        InteractionTheme {
+                            hovered: NodeOffset::new(Val::ZERO, Vw(-0.5)),
+                            pressed: NodeOffset::new(Val::ZERO, Vw(0.5)),
+                            ..default()
+                        },
+                        Patch(|entity| {
+                            entity.observe(buy_shop_offer);
+                        }),
+                    ));
+                    match &offer.item {
+                        ShopItem::Module(x) => {
+                            selector.with_child((
+                                module(&game_assets, module_config, x, player_deck.heat_capacity),
+                                Pickable::IGNORE,
+                            ));
+                        },
+                        ShopItem::Upgrade(x) => {
+                            selector.with_child((
+                                Name::new("UpgradeIcon"),
+                                ImageNode::from(x.icon(upgrade_config, relic_config)),
+                                Node {
+                                    width: Vw(6.66666),
+                                    aspect_ratio: Some(1.0),
+                                    ..default()
+                                },
+                                Pickable::IGNORE,
+                            ));
+                        },
+                    }
+                    selector.with_child((widget::small_label(price), Pickable::IGNORE));
+                }
+            });
+    }
+}
+
+fn buy_shop_offer(
+    trigger: Trigger<Pointer<Click>>,
+    mut commands: Commands,
+    selector_query: Query<(&ShopOfferSelector, &InteractionDisabled)>,
+) {
+    rq!(matches!(trigger.event.button, PointerButton::Primary));
+    let target = r!(trigger.get_target());
+    let (selector, disabled) = r!(selector_query.get(target));
+    rq!(!disabled.0);
+    commands.trigger(BuyShopOffer(selector.0));
+}
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct ShopOfferSelector(usize);
+
+impl Configure for ShopOfferSelector {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+    }
+}
+
+/// Lists the modules in storage that can be scrapped.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct ScrapRow;
+
+impl Configure for ScrapRow {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            Update,
+            Menu::Shop.on_update(
+                sync_scrap_row.in_set(UpdateSystems::SyncLate).run_if(
+                    resource_changed::<PlayerDeck>
+                        .or(resource_changed::<Salvage>)
+                        .or(any_match_filter::<Added<Self>>),
+                ),
+            ),
+        );
+    }
+}
+
+fn sync_scrap_row(
+    mut commands: Commands,
+    game_assets: Res<GameplayAssets>,
+    module_config: ConfigRef<ModuleConfig>,
+    projectile_config: ConfigRef<ProjectileConfig>,
+    shop_config: ConfigRef<ShopConfig>,
+    salvage: Res<Salvage>,
+    player_deck: Res<PlayerDeck>,
+    row_query: Query<Entity, With<ScrapRow>>,
+) {
+    let module_config = r!(module_config.get());
+    let projectile_config = r!(projectile_config.get());
+    let scrap_price = r!(shop_config.get()).scrap_price(&salvage);
+    let can_afford = scrap_price <= salvage.amount;
+
+    for entity in &row_query {
+        commands
+            .entity(entity)
+            .despawn_related::<Children>()
+            .with_children(|parent| {
+                parent.spawn((
+                    widget::small_label(format!(
+                        "Scrap a module from storage for [b]{scrap_price}[r] salvage:",
+                    )),
+                    Node {
+                        width: Percent(100.0),
+                        margin: UiRect::bottom(Vw(0.5)),
+                        ..default()
+                    },
+                ));
+                for (idx, slot) in player_deck.storage.iter().enumerate() {
+                    parent.spawn((
+                        Name::new("ScrapSelector"),
+                        ScrapSelector(idx),
+                        Button,
+                        Tooltip::fixed(
+                            Anchor::TopCenter,
+                            parse_rich(slot.description(
+                                module_config,
+                                projectile_config,
+                                player_deck.heat_capacity,
+                            )),
+                        ),
+                        Previous::<Interaction>::default(),
+                        InteractionGlassSfx,
+                        InteractionDisabled(!can_afford),
+                        Node::default(),
+                        NodeOffset::default(),
+                        InteractionTheme {
+                            hovered: NodeOffset::new(Val::ZERO, Vw(-0.5)),
+                            pressed: NodeOffset::new(Val::ZERO, Vw(0.5)),
+                            ..default()
+                        },
+                        Patch(|entity| {
+                            entity.observe(scrap_module);
+                        }),
+                        children![(
+                            module(&game_assets, module_config, slot, player_deck.heat_capacity,),
+                            Pickable::IGNORE,
+                        )],
+                    ));
+                }
+            });
+    }
+}
+
+fn scrap_module(
+    trigger: Trigger<Pointer<Click>>,
+    mut commands: Commands,
+    selector_query: Query<(&ScrapSelector, &InteractionDisabled)>,
+) {
+    rq!(matches!(trigger.event.button, PointerButton::Primary));
+    let target = r!(trigger.get_target());
+    let (selector, disabled) = r!(selector_query.get(target));
+    rq!(!disabled.0);
+    commands.trigger(ScrapModule(selector.0));
+}
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct ScrapSelector(usize);
+
+impl Configure for ScrapSelector {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+    }
+}
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct RepairButton;
+
+impl Configure for RepairButton {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            Update,
+            Menu::Shop.on_update(
+                sync_repair_button.in_set(UpdateSystems::SyncLate).run_if(
+                    resource_changed::<PlayerDeck>
+                        .or(resource_changed::<Salvage>)
+                        .or(any_match_filter::<Added<Self>>),
+                ),
+            ),
+        );
+    }
+}
+
+fn sync_repair_button(
+    shop_config: ConfigRef<ShopConfig>,
+    salvage: Res<Salvage>,
+    player_deck: Res<PlayerDeck>,
+    mut button_query: Query<&mut InteractionDisabled, With<RepairButton>>,
+) {
+    let shop_config = r!(shop_config.get());
+    let disabled =
+        player_deck.hull_damage <= f32::EPSILON || shop_config.repair_price > salvage.amount;
+    for mut button_disabled in &mut button_query {
+        button_disabled.0 = disabled;
+    }
+}
+
+/// Buy the shop offer at this index.
+#[derive(Event, Reflect, Clone, Debug)]
+pub struct BuyShopOffer(pub usize);
+
+impl Configure for BuyShopOffer {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_observer(apply_buy_shop_offer);
+    }
+}
+
+fn apply_buy_shop_offer(
+    trigger: Trigger<BuyShopOffer>,
+    upgrade_config: ConfigRef<UpgradeConfig>,
+    mut shop_stock: ResMut<ShopStock>,
+    mut salvage: ResMut<Salvage>,
+    mut player_deck: ResMut<PlayerDeck>,
+    mut upgrade_history: ResMut<UpgradeHistory>,
+) {
+    let upgrade_config = r!(upgrade_config.get());
+    let offer = r!(shop_stock.offers.get_mut(trigger.0));
+    rq!(!offer.sold && offer.price <= salvage.amount);
+
+    salvage.amount -= offer.price;
+    offer.sold = true;
+    match &mut offer.item {
+        ShopItem::Module(x) => player_deck.storage.push(x.clone()),
+        ShopItem::Upgrade(x) => x.apply(upgrade_config, &mut player_deck, &mut upgrade_history),
+    }
+}
+
+/// Scrap the module at this storage index.
+#[derive(Event, Reflect, Clone, Debug)]
+pub struct ScrapModule(pub usize);
+
+impl Configure for ScrapModule {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_observer(apply_scrap_module);
+    }
+}
+
+fn apply_scrap_module(
+    trigger: Trigger<ScrapModule>,
+    shop_config: ConfigRef<ShopConfig>,
+    mut salvage: ResMut<Salvage>,
+    mut player_deck: ResMut<PlayerDeck>,
+) {
+    let price = r!(shop_config.get()).scrap_price(&salvage);
+    rq!(trigger.0 < player_deck.storage.len() && price <= salvage.amount);
+
+    salvage.amount -= price;
+    salvage.scrapped += 1;
+    player_deck.storage.remove(trigger.0);
+}
+
+/// Repair the hull damage carried over to the next level.
+#[derive(Event, Reflect, Clone, Debug)]
+pub struct RepairHull;
+
+impl Configure for RepairHull {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_observer(apply_repair_hull);
+    }
+}
+
+fn apply_repair_hull(
+    _: Trigger<RepairHull>,
+    shop_config: ConfigRef<ShopConfig>,
+    mut salvage: ResMut<Salvage>,
+    mut player_deck: ResMut<PlayerDeck>,
+) {
+    let shop_config = r!(shop_config.get());
+    rq!(player_deck.hull_damage > f32::EPSILON && shop_config.repair_price <= salvage.amount);
+
+    salvage.amount -= shop_config.repair_price;
+    player_deck.hull_damage = (player_deck.hull_damage - shop_config.repair_amount).max(0.0);
+}
+
+/// Leave the shop and enter the next level.
+#[derive(Event, Reflect, Clone, Debug)]
+pub struct LeaveShop;
+
+impl Configure for LeaveShop {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_observer(enter_next_level);
+    }
+}
+
+fn enter_next_level(_: Trigger<LeaveShop>, mut level: NextMut<Level>) {
+    r!(level.get_mut()).0 += 1;
+}
@@ -1,203 +1,430 @@
-use rand::seq::index::sample_weighted;
-
 use crate::animation::offset::NodeOffset;
 use crate::deck::PlayerDeck;
 use crate::level::Level;
-use crate::level::LevelConfig;
 use crate::menu::Menu;
 use crate::menu::MenuRoot;
 use crate::module::Module;
 use crate::module::ModuleConfig;
 use crate::prelude::*;
-use crate::screen::gameplay::GameplayAssets;
+use crate::relic::RelicConfig;
+use crate::rng::GameRng;
+use crate::save::Resume;
 
 pub(super) fn plugin(app: &mut App) {
-    app.configure::<(UpgradeHistory, NextLevelButton, UpgradeSelector)>();
+    app.configure::<(
+        ConfigHandle<UpgradeConfig>,
+        UpgradeHistory,
+        ContinueButton,
+        UpgradeSelector,
+        PickUpgrades,
+    )>();
 
     app.add_systems(StateFlush, Menu::Upgrade.on_enter(spawn_upgrade_menu));
 }
 
-#[derive(Reflect, Clone, Debug)]
-pub enum Upgrade {
-    FluxCapacitor(usize),
-    QuantumCooler(f32),
-    AlienAlloy(f32),
-    StarterPack(Vec<Module>),
-    RepairPack(Vec<Module>),
-    MissilePack(Vec<Module>),
-    LaserPack(Vec<Module>),
-    FireballPack(Vec<Module>),
+#[derive(Asset, Reflect, Serialize, Deserialize, Default)]
+#[serde(deny_unknown_fields, default)]
+pub struct UpgradeConfig {
+    /// The number of upgrades offered after each level.
+    pub offer_count: usize,
+    /// The number of offered upgrades the player must pick.
+    pub pick_count: usize,
+    /// Actions that module packs will only use as a condition.
+    pub condition_only_actions: Vec<String>,
+    /// Actions that module packs will only use as an effect.
+    pub effect_only_actions: Vec<String>,
+    /// Offer slots, filled in order until the offer is full.
+    pub offers: Vec<OfferSlot>,
+    pub upgrades: HashMap<String, UpgradeInfo>,
 }
 
-impl Upgrade {
-    fn image(&self, game_assets: &GameplayAssets) -> Handle<Image> {
-        match self {
-            Upgrade::FluxCapacitor(_) => &game_assets.upgrade_capacitor,
-            Upgrade::QuantumCooler(_) => &game_assets.upgrade_cooler,
-            Upgrade::AlienAlloy(_) => &game_assets.upgrade_alloy,
-            Upgrade::StarterPack(_) => &game_assets.upgrade_pack_nothing,
-            Upgrade::RepairPack(_) => &game_assets.upgrade_pack_repair,
-            Upgrade::MissilePack(_) => &game_assets.upgrade_pack_missile,
-            Upgrade::LaserPack(_) => &game_assets.upgrade_pack_laser,
-            Upgrade::FireballPack(_) => &game_assets.upgrade_pack_fireball,
+impl Config for UpgradeConfig {
+    const FILE: &'static str = "upgrade.ron";
+
+    fn on_load(&mut self, world: &mut World) {
+        let asset_server = world.resource::<AssetServer>();
+        for upgrade in self.upgrades.values_mut() {
+            upgrade.icon = asset_server.load(&upgrade.icon_path);
         }
-        .clone()
     }
 
-    fn description(&self, module_config: &ModuleConfig) -> String {
-        match self {
-            Upgrade::FluxCapacitor(slots) => format!(
-                "[b]Flux Capacitor[r]\n\n\
-            Enhance your reactor with a state-of-the-art capacitor.\n\n\
-            [b]Reactor slots:[r] +{}",
-                slots,
-            ),
-            Upgrade::QuantumCooler(heat_capacity) => format!(
-                "[b]Quantum Cooler[r]\n\n\
-            Install a particle-level cooling system to limit overheating.\n\n\
-            [b]Reactor heat capacity:[r] +{}",
-                heat_capacity,
-            ),
-            Upgrade::AlienAlloy(max_health) => format!(
-                "[b]Alien Alloy[r]\n\n\
-            Reinforce your hull with a legendary alloy from another star.\n\n\
-            [b]Ship max health:[r] +{}",
-                max_health,
-            ),
-            Upgrade::StarterPack(modules) => {
-                format!(
-                    "[b]Starter Pack[r]\n\nUnpack three helpful new Starter modules.\n\n{}",
-                    modules
-                        .iter()
-                        .map(|x| x.short_description(module_config))
-                        .collect::<Vec<_>>()
-                        .join("\n"),
-                )
-            },
-            Upgrade::RepairPack(modules) => {
-                format!(
-                    "[b]Repair Pack[r]\n\nUnpack three new Repair modules.\n\n{}",
-                    modules
-                        .iter()
-                        .map(|x| x.short_description(module_config))
-                        .collect::<Vec<_>>()
-                        .join("\n"),
-                )
+    fn count_progress(&self, asset_server: &AssetServer) -> Progress {
+        let mut progress = true.into();
+        for upgrade in self.upgrades.values() {
+            progress += asset_server
+                .is_loaded_with_dependencies(&upgrade.icon)
+                .into();
+        }
+        progress
+    }
+}
+
+impl UpgradeConfig {
+    /// The weight of an upgrade at the given level, after repeat penalties.
+    pub fn weight(&self, key: &str, level: usize, upgrade_history: &UpgradeHistory) -> f32 {
+        let upgrade = rq!(self.upgrades.get(key));
+        curve(&upgrade.weight, level) * self.repeat_factor(key, upgrade_history)
+    }
+
+    /// The factor applied to an upgrade's weight and chance for each time it was taken.
+    fn repeat_factor(&self, key: &str, upgrade_history: &UpgradeHistory) -> f32 {
+        let upgrade = rq!(self.upgrades.get(key));
+        let taken = upgrade_history.taken.get(key).copied().unwrap_or_default();
+        1.0 / (1.0 + upgrade.repeat_penalty * taken as f32)
+    }
+
+    /// Pair an action with a partner action to create a module.
+    fn pair(&self, rng: &mut impl Rng, action: &str, partner: &str, idx: usize) -> Module {
+        let is_condition_only = |x: &str| self.condition_only_actions.iter().any(|y| y == x);
+        let is_effect_only = |x: &str| self.effect_only_actions.iter().any(|y| y == x);
+
+        let action_first = if is_condition_only(action) || is_effect_only(partner) {
+            true
+        } else if is_effect_only(action) || is_condition_only(partner) {
+            false
+        } else {
+            // Alternate the action between condition and effect, then choose at random.
+            match idx {
+                0 => true,
+                1 => false,
+                _ => rng.r#gen(),
+            }
+        };
+
+        if action_first {
+            Module::new(action, partner)
+        } else {
+            Module::new(partner, action)
+        }
+    }
+}
+
+/// A value that changes with the level. The last value continues for all later levels.
+pub fn curve(values: &[f32], level: usize) -> f32 {
+    values.get(level).or(values.last()).copied().unwrap_or(1.0)
+}
+
+/// A slot in the upgrade offer that draws upgrades from a pool by weight.
+#[derive(Reflect, Serialize, Deserialize, Default, Debug)]
+#[serde(deny_unknown_fields, default)]
+pub struct OfferSlot {
+    /// At most one slot in the same group will be offered.
+    pub group: String,
+    /// The slot is skipped unless all of these conditions are met.
+    pub requires: Vec<UpgradeCondition>,
+    /// The chance to offer anything at each level, scaled by the repeat penalty of the upgrade.
+    pub chance: Vec<f32>,
+    /// The upgrades to draw from.
+    pub pool: Vec<String>,
+    /// Keep drawing from this slot until the offer is full.
+    pub fill: bool,
+    /// Shuffle this slot's upgrades among the other shuffled upgrades.
+    pub shuffle: bool,
+}
+
+#[derive(Reflect, Serialize, Deserialize, Clone, Debug)]
+pub enum UpgradeCondition {
+    /// The reactor has fewer than this many slots.
+    ReactorSlotsBelow(usize),
+    /// The heat capacity is below this factor times the number of reactor slots squared.
+    HeatCapacityBelow(f32),
+    /// The player has fewer than this many relics.
+    RelicsBelow(usize),
+}
+
+impl UpgradeCondition {
+    fn is_met(&self, player_deck: &PlayerDeck) -> bool {
+        let slots = player_deck.reactor.len();
+        match *self {
+            Self::ReactorSlotsBelow(max) => slots < max,
+            Self::HeatCapacityBelow(factor) => {
+                player_deck.heat_capacity < factor * (slots * slots) as f32
             },
-            Upgrade::MissilePack(modules) => {
-                format!(
-                    "[b]Missile Pack[r]\n\nUnpack three new Missile modules.\n\n{}",
-                    modules
-                        .iter()
-                        .map(|x| x.short_description(module_config))
-                        .collect::<Vec<_>>()
-                        .join("\n"),
-                )
+            Self::RelicsBelow(max) => player_deck.relics.len() < max,
+        }
+    }
+}
+
+#[derive(Reflect, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct UpgradeInfo {
+    pub name: String,
+    #[serde(rename = "icon")]
+    pub icon_path: String,
+    #[serde(skip)]
+    pub icon: Handle<Image>,
+    /// Rich text where `{amount}` is replaced by the amount of the effect, `{modules}`
+    /// is replaced by the contents of a module pack, and `{relic}` is replaced by a relic's
+    /// description.
+    pub description: String,
+    pub effect: UpgradeEffect,
+    /// The weight at each level.
+    #[serde(default)]
+    pub weight: Vec<f32>,
+    /// Reduce the weight and chance of this upgrade each time it's taken.
+    #[serde(default)]
+    pub repeat_penalty: f32,
+}
+
+#[derive(Reflect, Serialize, Deserialize, Clone, Debug)]
+pub enum UpgradeEffect {
+    ReactorSlots(usize),
+    HeatCapacity(f32),
+    MaxHealth(f32),
+    ModulePack {
+        /// The action that every module in the pack will include.
+        action: String,
+        count: usize,
+        /// Weighted actions to pair with the pack's action.
+        partners: Vec<(String, f32)>,
+    },
+    /// A relic from this pool that the player doesn't have yet.
+    Relic(Vec<String>),
+}
+
+#[derive(Reflect, Serialize, Deserialize, Clone, Debug)]
+pub struct Upgrade {
+    /// The key of the upgrade in the upgrade config.
+    pub key: String,
+    /// The contents of a module pack.
+    pub modules: Vec<Module>,
+    /// The key of the relic in the relic config.
+    pub relic: Option<String>,
+}
+
+impl Upgrade {
+    pub fn generate(
+        rng: &mut impl Rng,
+        upgrade_config: &UpgradeConfig,
+        player_deck: &PlayerDeck,
+        key: &str,
+    ) -> Self {
+        let mut modules = vec![];
+        let mut relic = None;
+        match upgrade_config.upgrades.get(key).map(|x| &x.effect) {
+            Some(UpgradeEffect::ModulePack {
+                action,
+                count,
+                partners,
+            }) => {
+                for idx in 0..*count {
+                    let (partner, _) = c!(partners.choose_weighted(rng, |(_, weight)| *weight));
+                    modules.push(upgrade_config.pair(rng, action, partner, idx));
+                }
+                modules.shuffle(rng);
             },
-            Upgrade::LaserPack(modules) => {
-                format!(
-                    "[b]Laser Pack[r]\n\nUnpack three new Laser modules.\n\n{}",
-                    modules
-                        .iter()
-                        .map(|x| x.short_description(module_config))
-                        .collect::<Vec<_>>()
-                        .join("\n"),
-                )
+            Some(UpgradeEffect::Relic(pool)) => {
+                let unowned = pool
+                    .iter()
+                    .filter(|&x| !player_deck.relics.contains(x))
+                    .collect::<Vec<_>>();
+                relic = unowned.choose(rng).map(|&x| x.clone());
             },
-            Upgrade::FireballPack(modules) => {
-                format!(
-                    "[b]Fireball Pack[r]\n\nUnpack three powerful new Fireball modules.\n\n{}",
-                    modules
-                        .iter()
-                        .map(|x| x.short_description(module_config))
-                        .collect::<Vec<_>>()
-                        .join("\n"),
-                )
+            _ => {},
+        }
+
+        Self {
+            key: key.to_string(),
+            modules,
+            relic,
+        }
+    }
+
+    /// Apply the upgrade to the player deck, leaving an empty module pack behind.
+    pub fn apply(
+        &mut self,
+        upgrade_config: &UpgradeConfig,
+        player_deck: &mut PlayerDeck,
+        upgrade_history: &mut UpgradeHistory,
+    ) {
+        // Record upgrade history.
+        *upgrade_history.taken.entry(self.key.clone()).or_default() += 1;
+
+        // Upgrade deck.
+        let upgrade = r!(upgrade_config.upgrades.get(&self.key));
+        match upgrade.effect {
+            UpgradeEffect::ReactorSlots(slots) => player_deck
+                .reactor
+                .extend(std::iter::repeat_n(Module::EMPTY, slots)),
+            UpgradeEffect::HeatCapacity(heat_capacity) => {
+                player_deck.heat_capacity += heat_capacity
             },
+            UpgradeEffect::MaxHealth(max_health) => player_deck.max_health += max_health,
+            UpgradeEffect::ModulePack { .. } => player_deck.storage.append(&mut self.modules),
+            UpgradeEffect::Relic(_) => player_deck.relics.extend(self.relic.take()),
         }
     }
+
+    /// The upgrade's icon, or its relic's icon.
+    pub fn icon(
+        &self,
+        upgrade_config: &UpgradeConfig,
+        relic_config: &RelicConfig,
+    ) -> Handle<Image> {
+        self.relic
+            .as_ref()
+            .and_then(|x| relic_config.relics.get(x))
+            .map(|x| x.icon.clone())
+            .or_else(|| {
+                upgrade_config
+                    .upgrades
+                    .get(&self.key)
+                    .map(|x| x.icon.clone())
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn description(
+        &self,
+        upgrade_config: &UpgradeConfig,
+        module_config: &ModuleConfig,
+        relic_config: &RelicConfig,
+    ) -> String {
+        let upgrade = r!(upgrade_config.upgrades.get(&self.key));
+        let amount = match &upgrade.effect {
+            UpgradeEffect::ReactorSlots(slots) => slots.to_string(),
+            UpgradeEffect::HeatCapacity(amount) | UpgradeEffect::MaxHealth(amount) => {
+                amount.to_string()
+            },
+            UpgradeEffect::ModulePack { count, .. } => count.to_string(),
+            UpgradeEffect::Relic(_) => "1".to_string(),
+        };
+        let modules = self
+            .modules
+            .iter()
+            .map(|x| x.short_description(module_config))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let relic = self
+            .relic
+            .as_ref()
+            .and_then(|x| relic_config.relics.get(x))
+            .map(|x| x.description(module_config))
+            .unwrap_or_default();
+
+        format!(
+            "[b]{}[r]\n\n{}",
+            upgrade.name,
+            upgrade
+                .description
+                .replace("{amount}", &amount)
+                .replace("{modules}", &modules)
+                .replace("{relic}", &relic),
+        )
+    }
 }
 
 fn spawn_upgrade_menu(
     mut commands: Commands,
     menu_root: Res<MenuRoot>,
-    game_assets: Res<GameplayAssets>,
     module_config: ConfigRef<ModuleConfig>,
-    level_config: ConfigRef<LevelConfig>,
+    upgrade_config: ConfigRef<UpgradeConfig>,
+    relic_config: ConfigRef<RelicConfig>,
     level: CurrentRef<Level>,
     player_deck: Res<PlayerDeck>,
     upgrade_history: Res<UpgradeHistory>,
+    mut game_rng: ResMut<GameRng>,
 ) {
     let module_config = r!(module_config.get());
+    let upgrade_config = r!(upgrade_config.get());
+    let relic_config = r!(relic_config.get());
     let level = r!(level.get()).0;
-    let _level_config = r!(level_config.get());
 
     // Generate upgrade offers.
-    let upgrades = generate_upgrades(&mut thread_rng(), &player_deck, &upgrade_history, level);
+    let upgrades = generate_upgrades(
+        &mut game_rng.upgrade,
+        upgrade_config,
+        &player_deck,
+        &upgrade_history,
+        level,
+    );
+    let pick_count = upgrade_config.pick_count.min(upgrades.len());
 
     commands
         .entity(menu_root.ui)
         .with_child(widget::popup(children![
             widget::header("[b]They got away!"),
-            widget::label("Choose 3 upgrades:"),
-            offered_upgrades(&game_assets, module_config, upgrades),
+            widget::label(format!("Choose {pick_count} upgrades:")),
+            offered_upgrades(upgrade_config, module_config, relic_config, upgrades),
             widget::row_of_buttons(children![(
-                NextLevelButton,
-                widget::button("Pursue", enter_next_level),
-                Patch(|entity| {
-                    r!(entity.get_mut::<InteractionDisabled>()).0 = true;
+                ContinueButton,
+                widget::button("Continue", pick_upgrades),
+                Patch(move |entity| {
+                    r!(entity.get_mut::<InteractionDisabled>()).0 = pick_count > 0;
                 }),
             )])
         ]));
 }
 
-fn enter_next_level(
+fn pick_upgrades(
     trigger: Trigger<Pointer<Click>>,
+    mut commands: Commands,
     button_query: Query<&InteractionDisabled, With<Button>>,
-    mut selector_query: Query<&mut UpgradeSelector>,
-    mut player_deck: ResMut<PlayerDeck>,
-    mut upgrade_history: ResMut<UpgradeHistory>,
-    mut level: NextMut<Level>,
+    selector_query: Query<&UpgradeSelector>,
 ) {
     let target = r!(trigger.get_target());
     let disabled = r!(button_query.get(target));
     rq!(!disabled.0);
 
+    let picks = selector_query
+        .iter()
+        .filter(|x| x.selected)
+        .map(|x| x.idx)
+        .collect();
+    commands.trigger(PickUpgrades(picks));
+}
+
+/// Apply the offered upgrades at these indices and open the shop.
+#[derive(Event, Reflect, Clone, Debug)]
+pub struct PickUpgrades(pub Vec<usize>);
+
+impl Configure for PickUpgrades {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_observer(apply_picked_upgrades);
+    }
+}
+
+fn apply_picked_upgrades(
+    trigger: Trigger<PickUpgrades>,
+    mut selector_query: Query<&mut UpgradeSelector>,
+    upgrade_config: ConfigRef<UpgradeConfig>,
+    mut player_deck: ResMut<PlayerDeck>,
+    mut upgrade_history: ResMut<UpgradeHistory>,
+    mut menu: ResMut<NextStateStack<Menu>>,
+) {
+    let upgrade_config = r!(upgrade_config.get());
+
     // Apply upgrades.
     for mut selector in &mut selector_query {
-        cq!(selector.selected);
+        cq!(trigger.0.contains(&selector.idx));
 
-        // Record upgrade history.
-        match selector.upgrade {
-            Upgrade::StarterPack(_) => upgrade_history.took_starter_packs += 1,
-            Upgrade::FireballPack(_) => upgrade_history.took_fireball_packs += 1,
-            _ => {},
-        }
-
-        // Upgrade deck.
-        match &mut selector.upgrade {
-            Upgrade::FluxCapacitor(slots) => player_deck
-                .reactor
-                .extend(std::iter::repeat_n(Module::EMPTY, *slots)),
-            Upgrade::QuantumCooler(heat_capacity) => player_deck.heat_capacity += *heat_capacity,
-            Upgrade::AlienAlloy(max_health) => player_deck.max_health += *max_health,
-            Upgrade::StarterPack(modules)
-            | Upgrade::RepairPack(modules)
-            | Upgrade::MissilePack(modules)
-            | Upgrade::LaserPack(modules)
-            | Upgrade::FireballPack(modules) => player_deck.storage.append(modules),
-        }
+        selector
+            .upgrade
+            .apply(upgrade_config, &mut player_deck, &mut upgrade_history);
     }
 
-    // Enter next level.
-    r!(level.get_mut()).0 += 1;
+    // Replace the upgrade menu with the shop.
+    menu.release();
+    menu.clear();
+    menu.push(Menu::Shop);
+    menu.acquire();
 }
 
 fn offered_upgrades(
-    game_assets: &GameplayAssets,
+    upgrade_config: &UpgradeConfig,
     module_config: &ModuleConfig,
-    mut upgrades: Vec<Upgrade>,
+    relic_config: &RelicConfig,
+    upgrades: Vec<Upgrade>,
 ) -> impl Bundle {
+    let selectors = upgrades
+        .into_iter()
+        .enumerate()
+        .map(|(idx, upgrade)| {
+            upgrade_selector(upgrade_config, module_config, relic_config, idx, upgrade)
+        })
+        .collect::<Vec<_>>();
+
     (
         Name::new("OfferedUpgrades"),
         Node {
@@ -205,28 +432,23 @@ fn offered_upgrades(
             column_gap: Px(-1.0),
             ..Node::ROW.center()
         },
-        children![
-            upgrade_selector(game_assets, module_config, upgrades.remove(0)),
-            upgrade_selector(game_assets, module_config, upgrades.remove(0)),
-            upgrade_selector(game_assets, module_config, upgrades.remove(0)),
-            upgrade_selector(game_assets, module_config, upgrades.remove(0)),
-            upgrade_selector(game_assets, module_config, upgrades.remove(0)),
-            upgrade_selector(game_assets, module_config, upgrades.remove(0)),
-        ],
+        Children::spawn(SpawnIter(selectors.into_iter())),
     )
 }
 
 fn upgrade_selector(
-    game_assets: &GameplayAssets,
+    upgrade_config: &UpgradeConfig,
     module_config: &ModuleConfig,
+    relic_config: &RelicConfig,
+    idx: usize,
     upgrade: Upgrade,
 ) -> impl Bundle {
-    let image = upgrade.image(game_assets);
-    let description = upgrade.description(module_config);
+    let image = upgrade.icon(upgrade_config, relic_config);
+    let description = upgrade.description(upgrade_config, module_config, relic_config);
 
     (
         Name::new("UpgradeSelectorInteractionRegion"),
-        UpgradeSelector::new(upgrade),
+        UpgradeSelector::new(idx, upgrade),
         Button,
         Node {
             padding: UiRect::horizontal(Vw(1.2)).with_top(Vw(2.0)),
@@ -273,10 +495,15 @@ fn toggle_upgrade_selector(
     mut selector_query: Query<(Entity, &mut UpgradeSelector, &Children)>,
     mut node_query: Query<&mut Node>,
     mut disabled_query: Query<&mut InteractionDisabled>,
-    button_query: Query<Entity, With<NextLevelButton>>,
+    button_query: Query<Entity, With<ContinueButton>>,
+    upgrade_config: ConfigRef<UpgradeConfig>,
 ) {
     rq!(matches!(trigger.event.button, PointerButton::Primary));
     let target = r!(trigger.get_target());
+    // There may be fewer offers than picks.
+    let pick_count = r!(upgrade_config.get())
+        .pick_count
+        .min(selector_query.iter().count());
 
     // Toggle the selector.
     let disabled = r!(disabled_query.get(target));
@@ -296,22 +523,24 @@ fn toggle_upgrade_selector(
     let total_selected = selector_query.iter().filter(|(_, x, _)| x.selected).count();
     for (entity, selector, _) in &mut selector_query {
         let mut disabled = cq!(disabled_query.get_mut(entity));
-        if total_selected < 3 {
+        if total_selected < pick_count {
             disabled.0 = false;
         } else if !selector.selected {
             disabled.0 = true;
         }
     }
 
-    // Update interaction disabling of next level button.
+    // Update interaction disabling of continue button.
     for entity in &button_query {
-        cq!(disabled_query.get_mut(entity)).0 = total_selected < 3;
+        cq!(disabled_query.get_mut(entity)).0 = total_selected < pick_count;
     }
 }
 
 #[derive(Component, Reflect, Debug)]
 #[reflect(Component)]
 struct UpgradeSelector {
+    /// The index of the upgrade in the offer.
+    idx: usize,
     upgrade: Upgrade,
     selected: bool,
 }
@@ -323,26 +552,31 @@ impl Configure for UpgradeSelector {
 }
 
 impl UpgradeSelector {
-    fn new(upgrade: Upgrade) -> Self {
+    fn new(idx: usize, upgrade: Upgrade) -> Self {
         Self {
+            idx,
             upgrade,
             selected: false,
         }
     }
 }
 
-#[derive(Resource, Reflect, Default, Debug)]
+#[derive(Resource, Reflect, Serialize, Deserialize, Default, Clone, Debug)]
 #[reflect(Resource)]
-struct UpgradeHistory {
-    took_starter_packs: usize,
-    took_fireball_packs: usize,
+#[serde(default)]
+pub struct UpgradeHistory {
+    /// The number of times each upgrade was taken.
+    pub taken: HashMap<String, usize>,
 }
 
 impl Configure for UpgradeHistory {
     fn configure(app: &mut App) {
         app.register_type::<Self>();
         app.init_resource::<Self>();
-        app.add_systems(StateFlush, Level(0).on_enter(reset_upgrade_history));
+        app.add_systems(
+            StateFlush,
+            Level(0).on_enter(reset_upgrade_history.run_if(not(resource_exists::<Resume>))),
+        );
     }
 }
 
@@ -352,139 +586,71 @@ fn reset_upgrade_history(mut upgrade_history: ResMut<UpgradeHistory>) {
 
 #[derive(Component, Reflect, Debug, Copy, Clone)]
 #[reflect(Component)]
-struct NextLevelButton;
+struct ContinueButton;
 
-impl Configure for NextLevelButton {
+impl Configure for ContinueButton {
     fn configure(app: &mut App) {
         app.register_type::<Self>();
     }
 }
 
-fn generate_upgrades(
+pub fn generate_upgrades(
     rng: &mut impl Rng,
+    upgrade_config: &UpgradeConfig,
     player_deck: &PlayerDeck,
     upgrade_history: &UpgradeHistory,
     level: usize,
 ) -> Vec<Upgrade> {
     let mut upgrades = vec![];
-
-    // Offer primary upgrades.
-    if player_deck.heat_capacity
-        < 0.18 * (player_deck.reactor.len() * player_deck.reactor.len()) as f32
-    {
-        upgrades.push(Upgrade::QuantumCooler(4.0));
-    } else if player_deck.reactor.len() < 18 {
-        upgrades.push(Upgrade::FluxCapacitor(3));
-    } else {
-        upgrades.push(if rng.gen_bool(0.9) {
-            Upgrade::AlienAlloy(50.0)
-        } else {
-            Upgrade::QuantumCooler(4.0)
-        });
-    }
-    upgrades.push(if rng.gen_bool(0.9) {
-        Upgrade::AlienAlloy(50.0)
-    } else {
-        Upgrade::QuantumCooler(4.0)
-    });
-
-    // Offer module packs.
-    // TODO: Get weight from level / level config.
-    if rng.gen_bool(
-        (0.15 * level as f64 / (upgrade_history.took_fireball_packs + 1) as f64).clamp(0.0, 1.0),
-    ) {
-        upgrades.push(Upgrade::FireballPack(vec![]));
-    }
-    // TODO: Get weight from level / level config.
-    if rng.gen_bool(
-        (0.2 * (level + 1) as f64 / (upgrade_history.took_starter_packs + 1) as f64)
-            .clamp(0.0, 1.0),
-    ) {
-        upgrades.push(Upgrade::StarterPack(vec![]));
-    }
-    for _ in upgrades.len()..6 {
-        // TODO: Get weights from level / level config.
-        let choice = cq!(sample_weighted(
-            rng,
-            5,
-            |x| [1.0, 0.8, 0.6, 0.1, 0.08][x],
-            1,
-        ))
-        .index(0);
-        upgrades.push(match choice {
-            0 => Upgrade::MissilePack(vec![]),
-            1 => Upgrade::RepairPack(vec![]),
-            2 => Upgrade::LaserPack(vec![]),
-            3 => Upgrade::StarterPack(vec![]),
-            4 => Upgrade::FireballPack(vec![]),
-            _ => unreachable!(),
-        });
-    }
-    upgrades[2..].shuffle(rng);
-
-    // Populate module packs.
-    for upgrade in &mut upgrades {
-        let (action, modules) = match upgrade {
-            Upgrade::StarterPack(modules) => ("", modules),
-            Upgrade::RepairPack(modules) => ("repair", modules),
-            Upgrade::MissilePack(modules) => ("missile", modules),
-            Upgrade::LaserPack(modules) => ("laser", modules),
-            Upgrade::FireballPack(modules) => ("fireball", modules),
-            _ => continue,
-        };
-
-        let all_actions = ["missile", "repair", "laser", "", "fireball"];
-        let mut other_actions = vec![];
-        for _ in 0..3 {
-            // TODO: Get weights from level / level config.
-            other_actions.push(*c!(all_actions.choose_weighted(rng, |&x| match x {
-                "missile" => 1.0,
-                "repair" => 0.6,
-                "laser" => 0.7,
-                x @ "" if x == action => 0.0,
-                "" => 0.1,
-                x @ "fireball" if x == action => 0.0,
-                "fireball" => 0.08,
-                _ => 0.0,
-            })));
-        }
-
-        match action {
-            "" => {
-                for other in other_actions {
-                    modules.push(Module::new(action, other));
+    let mut shuffled = vec![];
+    let mut groups = HashSet::<&str>::default();
+
+    // Fill offer slots in order.
+    for offer in &upgrade_config.offers {
+        cq!(offer.group.is_empty() || !groups.contains(offer.group.as_str()));
+        cq!(offer.requires.iter().all(|x| x.is_met(player_deck)));
+
+        let mut failed_rolls = 0;
+        while upgrades.len() < upgrade_config.offer_count {
+            let Ok(key) = offer.pool.choose_weighted(rng, |key| {
+                upgrade_config.weight(key, level, upgrade_history)
+            }) else {
+                break;
+            };
+            let chance =
+                curve(&offer.chance, level) * upgrade_config.repeat_factor(key, upgrade_history);
+            if !rng.gen_bool(chance.clamp(0.0, 1.0) as f64) {
+                // Fill slots retry a failed roll so the offer doesn't come up short.
+                failed_rolls += 1;
+                if offer.fill && failed_rolls < upgrade_config.offer_count {
+                    continue;
                 }
-            },
-            "fireball" => {
-                for other in other_actions {
-                    modules.push(Module::new(other, action));
-                }
-            },
-            _ => {
-                if matches!(other_actions[0], "") {
-                    modules.push(Module::new(other_actions[0], action));
-                } else {
-                    modules.push(Module::new(action, other_actions[0]));
-                }
-
-                if matches!(other_actions[1], "fireball") {
-                    modules.push(Module::new(action, other_actions[1]));
-                } else {
-                    modules.push(Module::new(other_actions[1], action));
-                }
-
-                if matches!(other_actions[2], "fireball")
-                    || (!matches!(other_actions[2], "") && rng.r#gen())
-                {
-                    modules.push(Module::new(action, other_actions[2]));
-                } else {
-                    modules.push(Module::new(other_actions[2], action));
-                }
-
-                modules.shuffle(rng);
-            },
+                break;
+            }
+
+            if !offer.group.is_empty() {
+                groups.insert(offer.group.as_str());
+            }
+            if offer.shuffle {
+                shuffled.push(upgrades.len());
+            }
+            upgrades.push(Upgrade::generate(rng, upgrade_config, player_deck, key));
+
+            if !offer.fill {
+                break;
+            }
         }
     }
 
+    // Shuffle the shuffled slots among themselves.
+    let mut shuffled_upgrades = shuffled
+        .iter()
+        .map(|&idx| upgrades[idx].clone())
+        .collect::<Vec<_>>();
+    shuffled_upgrades.shuffle(rng);
+    for (&idx, upgrade) in shuffled.iter().zip(shuffled_upgrades) {
+        upgrades[idx] = upgrade;
+    }
+
     upgrades
 }
@@ -0,0 +1,238 @@
+use crate::combat::damage::OnDamage;
+use crate::combat::death::Dead;
+use crate::combat::death::DespawnOnDeath;
+use crate::combat::death::DieOnLifetime;
+use crate::combat::faction::Faction;
+use crate::combat::health::Health;
+use crate::core::physics::GameLayer;
+use crate::level::CurrentLevelSetup;
+use crate::level::Level;
+use crate::level::set_current_level_setup;
+use crate::phase::Phase;
+use crate::prelude::*;
+use crate::rng::GameRng;
+use crate::ship::EnemyShip;
+use crate::ship::PlayerShip;
+use crate::stats::Stats;
+
+pub(super) fn plugin(app: &mut App) {
+    app.configure::<(LevelModifiers, Asteroid)>();
+}
+
+/// An environmental hazard that changes the rules of a level.
+#[derive(Reflect, Serialize, Deserialize, Clone, Debug)]
+pub enum LevelModifier {
+    /// Every module activation generates this much extra heat.
+    IonStorm(f32),
+    /// Projectiles fly at this fraction of their usual speed.
+    Nebula(f32),
+    /// Both sides take this much damage at the end of each round.
+    SolarFlare(f32),
+    /// Asteroids drift across the battlefield whenever the player opens fire,
+    /// damaging any ship they hit.
+    AsteroidField { count: usize, damage: f32 },
+}
+
+impl LevelModifier {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::IonStorm(_) => "Ion storm",
+            Self::Nebula(_) => "Nebula",
+            Self::SolarFlare(_) => "Solar flare",
+            Self::AsteroidField { .. } => "Asteroid field",
+        }
+    }
+
+    pub fn description(&self) -> String {
+        match self {
+            Self::IonStorm(x) => format!("Modules gain {x} extra heat when activated."),
+            Self::Nebula(x) => format!(
+                "Projectiles fly at {}% of their usual speed.",
+                (100.0 * x).round(),
+            ),
+            Self::SolarFlare(x) => format!("Every ship takes {x} damage at the end of each round."),
+            Self::AsteroidField { count, damage } => format!(
+                "{count} asteroids drift across the battlefield each round,\n\
+                dealing {damage} damage to any ship they hit.",
+            ),
+        }
+    }
+}
+
+/// The modifiers of the current level.
+#[derive(Resource, Reflect, Serialize, Deserialize, Clone, Default, Debug)]
+#[reflect(Resource)]
+#[serde(transparent)]
+pub struct LevelModifiers(pub Vec<LevelModifier>);
+
+impl Configure for LevelModifiers {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.init_resource::<Self>();
+        app.add_systems(
+            StateFlush,
+            (
+                Level::ANY.on_edge(
+                    clear_level_modifiers,
+                    set_level_modifiers.after(set_current_level_setup),
+                ),
+                (Phase::Enemy, Phase::Setup).on_trans(deal_solar_flare_damage),
+            ),
+        );
+    }
+}
+
+impl LevelModifiers {
+    /// The extra heat each module activation generates.
+    pub fn extra_heat(&self) -> f32 {
+        self.0
+            .iter()
+            .map(|x| match x {
+                LevelModifier::IonStorm(x) => *x,
+                _ => 0.0,
+            })
+            .sum()
+    }
+
+    /// The factor to scale projectile speed by.
+    pub fn projectile_speed(&self) -> f32 {
+        self.0
+            .iter()
+            .map(|x| match x {
+                LevelModifier::Nebula(x) => x.max(0.0),
+                _ => 1.0,
+            })
+            .product()
+    }
+
+    /// The damage every ship takes at the end of each round.
+    pub fn solar_flare_damage(&self) -> f32 {
+        self.0
+            .iter()
+            .map(|x| match x {
+                LevelModifier::SolarFlare(x) => x.max(0.0),
+                _ => 0.0,
+            })
+            .sum()
+    }
+
+    /// The number of asteroids to spawn each round and the damage each deals.
+    fn asteroids(&self) -> impl Iterator<Item = (usize, f32)> + '_ {
+        self.0.iter().filter_map(|x| match x {
+            LevelModifier::AsteroidField { count, damage } => Some((*count, *damage)),
+            _ => None,
+        })
+    }
+}
+
+fn clear_level_modifiers(mut level_modifiers: ResMut<LevelModifiers>) {
+    level_modifiers.0.clear();
+}
+
+fn set_level_modifiers(
+    level_setup: Res<CurrentLevelSetup>,
+    mut level_modifiers: ResMut<LevelModifiers>,
+) {
+    *level_modifiers = level_setup.0.modifiers.clone();
+}
+
+fn deal_solar_flare_damage(
+    mut commands: Commands,
+    level_modifiers: Res<LevelModifiers>,
+    ship_query: Query<(Entity, &Faction), (Or<(With<PlayerShip>, With<EnemyShip>)>, Without<Dead>)>,
+    mut stats: ResMut<Stats>,
+) {
+    let damage = level_modifiers.solar_flare_damage();
+    rq!(damage > 0.0);
+    for (entity, faction) in &ship_query {
+        match faction {
+            Faction::Player => stats.damage_taken += damage,
+            Faction::Enemy => stats.damage_given += damage,
+        }
+        commands.entity(entity).trigger(OnDamage(damage));
+    }
+}
+
+/// A rock drifting across the battlefield that damages the first ship it hits.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct Asteroid(pub f32);
+
+impl Configure for Asteroid {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(StateFlush, Phase::Player.on_enter(spawn_asteroids));
+    }
+}
+
+impl Asteroid {
+    /// The x position asteroids spawn at on either side of the battlefield.
+    const SPAWN_X: f32 = 240.0;
+    /// How long an asteroid drifts before it's gone.
+    const LIFETIME: f32 = 12.0;
+}
+
+fn spawn_asteroids(
+    mut commands: Commands,
+    level_modifiers: Res<LevelModifiers>,
+    mut game_rng: ResMut<GameRng>,
+) {
+    let rng = &mut game_rng.combat;
+    for (count, damage) in level_modifiers.asteroids() {
+        for _ in 0..count {
+            let side = if rng.r#gen() { -1.0 } else { 1.0 };
+            let position = vec2(
+                59.0 - side * Asteroid::SPAWN_X,
+                rng.gen_range(-60.0..=110.0),
+            );
+            let velocity = vec2(
+                side * rng.gen_range(40.0..=80.0),
+                rng.gen_range(-10.0..=10.0),
+            );
+            let size = rng.gen_range(6.0..=14.0);
+
+            commands
+                .spawn((
+                    Name::new("Asteroid"),
+                    Asteroid(damage),
+                    Sprite::from_color(Color::srgb(0.45, 0.4, 0.38), Vec2::splat(size)),
+                    Transform::from_translation(position.extend(1.0))
+                        .with_rotation(Quat::turn_fraction(rng.r#gen())),
+                    RigidBody::Dynamic,
+                    Mass(1.0),
+                    LinearVelocity(velocity),
+                    AngularVelocity(rng.gen_range(-2.0..=2.0)),
+                    Collider::circle(size / 2.0),
+                    Sensor,
+                    CollisionLayers::new(GameLayer::Default, [GameLayer::Player, GameLayer::Enemy]),
+                    CollisionEventsEnabled,
+                    DieOnLifetime(Asteroid::LIFETIME),
+                    DespawnOnDeath,
+                    DespawnOnExitState::<Level>::default(),
+                ))
+                .observe(damage_ship_on_asteroid_collision);
+        }
+    }
+}
+
+fn damage_ship_on_asteroid_collision(
+    trigger: Trigger<OnCollisionStart>,
+    mut commands: Commands,
+    asteroid_query: Query<&Asteroid>,
+    health_query: Query<&Faction, (With<Health>, Without<Dead>)>,
+    mut stats: ResMut<Stats>,
+) {
+    let asteroid = r!(trigger.get_target());
+    let damage = r!(asteroid_query.get(asteroid)).0;
+    let ship = trigger.body.unwrap_or(trigger.collider);
+    let faction = rq!(health_query.get(ship));
+
+    // Record stats:
+    match faction {
+        Faction::Player => stats.damage_taken += damage,
+        Faction::Enemy => stats.damage_given += damage,
+    }
+
+    commands.entity(asteroid).try_despawn();
+    commands.entity(ship).trigger(OnDamage(damage));
+}
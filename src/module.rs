@@ -1,14 +1,11 @@
 use crate::combat::faction::Faction;
-use crate::combat::health::OnHeal;
-use crate::core::audio::AudioSettings;
-use crate::core::audio::sfx_audio;
 use crate::deck::EnemyDeck;
 use crate::deck::PlayerDeck;
-use crate::level::Level;
+use crate::effect::ActionEffect;
+use crate::effect::EffectHandlers;
+use crate::effect::EffectInput;
 use crate::prelude::*;
 use crate::projectile::ProjectileConfig;
-use crate::screen::gameplay::GameplayAssets;
-use crate::ship::Weapon;
 use crate::stats::Stats;
 
 pub(super) fn plugin(app: &mut App) {
@@ -18,6 +15,10 @@ pub(super) fn plugin(app: &mut App) {
 #[derive(Asset, Reflect, Serialize, Deserialize, Default)]
 #[serde(deny_unknown_fields, default)]
 pub struct ModuleConfig {
+    /// The number of columns in the reactor grid, which determines which slots are adjacent.
+    pub reactor_columns: usize,
+    pub heat: HeatConfig,
+    pub condition_icons: ConditionIcons,
     pub actions: HashMap<String, ActionInfo>,
 }
 
@@ -26,6 +27,7 @@ impl Config for ModuleConfig {
 
     fn on_load(&mut self, world: &mut World) {
         let asset_server = world.resource::<AssetServer>();
+        self.condition_icons.load(asset_server);
         for action in self.actions.values_mut() {
             action.load(asset_server);
         }
@@ -33,6 +35,7 @@ impl Config for ModuleConfig {
 
     fn count_progress(&self, asset_server: &AssetServer) -> Progress {
         let mut progress = true.into();
+        progress += self.condition_icons.count_progress(asset_server);
         for action in self.actions.values() {
             progress += action.count_progress(asset_server);
         }
@@ -41,6 +44,10 @@ impl Config for ModuleConfig {
 }
 
 impl ModuleConfig {
+    pub fn reactor_columns(&self) -> usize {
+        self.reactor_columns.max(1)
+    }
+
     pub fn action(&self, key: impl AsRef<str>) -> &ActionInfo {
         self.actions
             .get(key.as_ref())
@@ -48,6 +55,81 @@ impl ModuleConfig {
     }
 }
 
+/// Rules for how reactor modules lose heat and what happens when they overheat.
+#[derive(Reflect, Serialize, Deserialize, Default, Clone, Debug)]
+#[serde(deny_unknown_fields, default)]
+pub struct HeatConfig {
+    /// How much heat every reactor module loses at the end of each round.
+    pub dissipation: f32,
+    /// If set, overheated modules can still trigger, damaging the player ship by this amount.
+    pub meltdown_damage: Option<f32>,
+}
+
+/// An effect of a reactor module on its neighbors in the reactor grid.
+#[derive(Reflect, Serialize, Deserialize, Clone, Debug)]
+pub enum AdjacencyEffect {
+    /// Neighboring modules perform their effects with this much extra flux.
+    Boost(f32),
+    /// Spread this fraction of the heat the module gains evenly across its neighbors.
+    ShareHeat(f32),
+    /// The chain can only continue from the module into a neighboring slot.
+    ChainAdjacent,
+}
+
+impl AdjacencyEffect {
+    /// A line describing this effect for a module tooltip.
+    pub fn description(&self) -> String {
+        match self {
+            Self::Boost(x) => format!("[b]Adjacent:[r] {x:+} flux to neighbors"),
+            Self::ShareHeat(x) => format!(
+                "[b]Adjacent:[r] shares {}% of its heat with neighbors",
+                (100.0 * x).round(),
+            ),
+            Self::ChainAdjacent => "[b]Adjacent:[r] only chains into neighbors".to_string(),
+        }
+    }
+}
+
+/// Icons for conditions that don't name a single action.
+#[derive(Reflect, Serialize, Deserialize, Default)]
+#[serde(deny_unknown_fields, default)]
+pub struct ConditionIcons {
+    #[serde(rename = "any")]
+    any_path: String,
+    #[serde(skip)]
+    pub any: Handle<Image>,
+    /// Drawn over the icon of the negated condition.
+    #[serde(rename = "not")]
+    not_path: String,
+    #[serde(skip)]
+    pub not: Handle<Image>,
+    #[serde(rename = "chain")]
+    chain_path: String,
+    #[serde(skip)]
+    pub chain: Handle<Image>,
+    #[serde(rename = "flux")]
+    flux_path: String,
+    #[serde(skip)]
+    pub flux: Handle<Image>,
+}
+
+impl ConditionIcons {
+    fn load(&mut self, asset_server: &AssetServer) {
+        self.any = asset_server.load(&self.any_path);
+        self.not = asset_server.load(&self.not_path);
+        self.chain = asset_server.load(&self.chain_path);
+        self.flux = asset_server.load(&self.flux_path);
+    }
+
+    fn count_progress(&self, asset_server: &AssetServer) -> Progress {
+        let mut progress = Progress::default();
+        for icon in [&self.any, &self.not, &self.chain, &self.flux] {
+            progress += asset_server.is_loaded_with_dependencies(icon).into();
+        }
+        progress
+    }
+}
+
 #[derive(Reflect, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct ActionInfo {
@@ -68,11 +150,16 @@ pub struct ActionInfo {
     #[serde(skip)]
     pub effect_icon: Handle<Image>,
     #[serde(default)]
-    pub effect_projectile: String,
+    pub effect_heat: f32,
+    /// How much heat the neighboring reactor modules lose when this action is triggered.
     #[serde(default)]
-    pub effect_heal: f32,
+    pub effect_cooling: f32,
+    /// The effects performed in order when this action is triggered.
     #[serde(default)]
-    pub effect_heat: f32,
+    pub effects: Vec<ActionEffect>,
+    /// Effects on the neighboring reactor slots of a module with this action as its effect.
+    #[serde(default)]
+    pub adjacency: Vec<AdjacencyEffect>,
 }
 
 impl ActionInfo {
@@ -93,6 +180,117 @@ impl ActionInfo {
     }
 }
 
+/// A module condition, parsed from the module's condition string:
+///
+/// - `""`: The start of a new chain.
+/// - `"missile"`: After the named action.
+/// - `"*"`: After any action.
+/// - `"!missile"`: After any action except this condition.
+/// - `"missile|laser"`: After any of these conditions.
+/// - `"chain>=3"`: After any action, once the chain is at least this long.
+/// - `"flux>=3"`: After any action, once flux is at least this high.
+///
+/// `!` binds looser than `|`, so `"!missile|laser"` is after any action except missile or laser.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Condition {
+    Start,
+    Action(String),
+    Any,
+    Not(Box<Condition>),
+    OneOf(Vec<Condition>),
+    ChainAtLeast(f32),
+    FluxAtLeast(f32),
+}
+
+impl Condition {
+    pub fn parse(text: &str) -> Self {
+        let text = text.trim();
+        if text.is_empty() {
+            Self::Start
+        } else if text == "*" {
+            Self::Any
+        } else if let Some(rest) = text.strip_prefix('!') {
+            Self::Not(Box::new(Self::parse(rest)))
+        } else if text.contains('|') {
+            Self::OneOf(text.split('|').map(Self::parse).collect())
+        } else if let Some(n) = text.strip_prefix("chain>=") {
+            Self::parse_threshold(text, n).map_or_else(Self::Action, Self::ChainAtLeast)
+        } else if let Some(n) = text.strip_prefix("flux>=") {
+            Self::parse_threshold(text, n).map_or_else(Self::Action, Self::FluxAtLeast)
+        } else {
+            Self::Action(text.to_string())
+        }
+    }
+
+    /// Parse the number in a threshold condition, or warn and return the condition text.
+    fn parse_threshold(text: &str, number: &str) -> Result<f32, String> {
+        number.trim().parse().map_err(|_| {
+            warn!("Invalid number in module condition {text:?}, treating it as an action");
+            text.to_string()
+        })
+    }
+
+    /// Determine whether a module with this condition can trigger next in the chain.
+    ///
+    /// Only [`Condition::Start`] can begin a new chain, so every condition fails after no action.
+    pub fn is_met(&self, last_action: &str, chain: f32, flux: f32) -> bool {
+        if last_action.is_empty() {
+            return false;
+        }
+        match self {
+            Self::Start => false,
+            Self::Action(action) => action == last_action,
+            Self::Any => true,
+            Self::Not(x) => !x.is_met(last_action, chain, flux),
+            Self::OneOf(xs) => xs.iter().any(|x| x.is_met(last_action, chain, flux)),
+            Self::ChainAtLeast(n) => chain >= *n,
+            Self::FluxAtLeast(n) => flux >= *n,
+        }
+    }
+
+    pub fn name(&self, module_config: &ModuleConfig) -> String {
+        match self {
+            Self::Start => module_config.action("").condition_name.clone(),
+            Self::Action(action) => module_config.action(action).condition_name.clone(),
+            Self::Any => "Any".to_string(),
+            Self::Not(x) => format!("Not {}", x.name(module_config)),
+            Self::OneOf(xs) => xs
+                .iter()
+                .map(|x| x.name(module_config))
+                .collect::<Vec<_>>()
+                .join(" or "),
+            Self::ChainAtLeast(n) => format!("Chain {n}+"),
+            Self::FluxAtLeast(n) => format!("Flux {n}+"),
+        }
+    }
+
+    /// The start of a module description, e.g. "After launching a missile, ".
+    pub fn description(&self, module_config: &ModuleConfig) -> String {
+        match self {
+            Self::Start => module_config.action("").condition_description.clone(),
+            Self::Action(action) => module_config.action(action).condition_description.clone(),
+            Self::Any => "After any action, ".to_string(),
+            Self::Not(x) => format!("After any action but [b]{}[r], ", x.name(module_config)),
+            Self::OneOf(_) => format!("After [b]{}[r], ", self.name(module_config)),
+            Self::ChainAtLeast(n) => format!("Once the chain reaches {n}, "),
+            Self::FluxAtLeast(n) => format!("Once flux reaches {n}x, "),
+        }
+    }
+
+    /// The excess heat gained by a module with this condition when triggered.
+    pub fn heat(&self, module_config: &ModuleConfig) -> f32 {
+        match self {
+            Self::Start => module_config.action("").condition_heat,
+            Self::Action(action) => module_config.action(action).condition_heat,
+            Self::OneOf(xs) => xs
+                .iter()
+                .map(|x| x.heat(module_config))
+                .fold(f32::NEG_INFINITY, f32::max),
+            _ => 0.0,
+        }
+    }
+}
+
 #[derive(Reflect, Clone, Default, Debug, Serialize, Deserialize)]
 #[serde(deny_unknown_fields, default)]
 pub struct Module {
@@ -100,6 +298,8 @@ pub struct Module {
     pub effect: String,
     pub status: ModuleStatus,
     pub heat: f32,
+    /// Extra flux from neighboring modules, set when the module is triggered.
+    pub boost: f32,
 }
 
 impl Module {
@@ -108,6 +308,7 @@ impl Module {
         effect: String::new(),
         status: ModuleStatus::SlotEmpty,
         heat: 0.0,
+        boost: 0.0,
     };
 
     pub fn new(condition: impl Into<String>, effect: impl Into<String>) -> Self {
@@ -116,13 +317,14 @@ impl Module {
             effect: effect.into(),
             status: ModuleStatus::FaceUp,
             heat: 0.0,
+            boost: 0.0,
         }
     }
 
     pub fn short_description(&self, module_config: &ModuleConfig) -> String {
         format!(
             "[b]{}[r] -> [b]{}[r]",
-            module_config.action(&self.condition).condition_name,
+            Condition::parse(&self.condition).name(module_config),
             module_config.action(&self.effect).effect_name,
         )
     }
@@ -133,21 +335,23 @@ impl Module {
         projectile_config: &ProjectileConfig,
         heat_capacity: f32,
     ) -> String {
+        let round = |x: f32| (10.0 * x).round() / 10.0;
         let header = self.short_description(module_config);
         let heat = if matches!(self.status, ModuleStatus::SlotOverheated) {
             " (OVERHEATED)".to_string()
         } else {
-            format!(" (heat: {}/{})", self.heat, heat_capacity)
+            format!(" (heat: {}/{})", round(self.heat), heat_capacity)
         };
         match self.status {
             ModuleStatus::FaceDown => header.to_string(),
             ModuleStatus::SlotEmpty => format!("{header}\n\nEmpty slot"),
             _ => {
-                let condition = &module_config.action(&self.condition);
+                let condition = Condition::parse(&self.condition);
+                let condition_heat = condition.heat(module_config);
                 let effect = &module_config.action(&self.effect);
                 let body = format!(
                     "{}{}.",
-                    condition.condition_description,
+                    condition.description(module_config),
                     if self.condition == self.effect {
                         &effect.effect_repeat_description
                     } else {
@@ -156,25 +360,49 @@ impl Module {
                 );
 
                 let mut stats = String::new();
-                if let Some(projectile) =
-                    projectile_config.projectiles.get(&effect.effect_projectile)
+                for line in effect
+                    .effects
+                    .iter()
+                    .filter_map(|x| x.description(projectile_config))
                 {
+                    stats += &format!("\n- {line}");
+                }
+                if condition_heat + effect.effect_heat != 0.0 {
                     stats += &format!(
-                        "\n- [b]Damage:[r] {} times flux",
-                        (10.0 * projectile.damage).round() / 10.0,
-                    );
+                        "\n- [b]Excess heat:[r] {:+}",
+                        round(condition_heat + effect.effect_heat),
+                    )
+                }
+                for adjacency in &effect.adjacency {
+                    stats += &format!("\n- {}", adjacency.description());
                 }
-                if effect.effect_heal != 0.0 {
+                if effect.effect_cooling > 0.0 {
                     stats += &format!(
-                        "\n- [b]Heal:[r] {} times flux",
-                        (10.0 * effect.effect_heal).round() / 10.0,
+                        "\n- [b]Cooling:[r] {} per neighboring module",
+                        round(effect.effect_cooling),
                     );
                 }
-                if condition.condition_heat + effect.effect_heat != 0.0 {
-                    stats += &format!(
-                        "\n- [b]Excess heat:[r] {:+}",
-                        (10.0 * (condition.condition_heat + effect.effect_heat)).round() / 10.0,
+                if matches!(self.status, ModuleStatus::SlotOverheated) {
+                    stats += &match module_config.heat.meltdown_damage {
+                        Some(damage) => format!(
+                            "\n- [b]Meltdown:[r] {} damage to your ship when triggered",
+                            round(damage),
+                        ),
+                        None => "\n- [b]Disabled[r] until cooled".to_string(),
+                    };
+                }
+                if module_config.heat.dissipation > 0.0
+                    && matches!(
+                        self.status,
+                        ModuleStatus::SlotInactive
+                            | ModuleStatus::SlotActive
+                            | ModuleStatus::SlotOverheated
                     )
+                {
+                    stats += &format!(
+                        "\n- [b]Dissipation:[r] {} heat per round",
+                        round(module_config.heat.dissipation),
+                    );
                 }
                 if !stats.is_empty() {
                     stats = format!("\n{stats}");
@@ -202,6 +430,8 @@ pub struct OnAction {
     pub action: String,
     pub source: Entity,
     pub target: Entity,
+    /// Extra flux for this action only.
+    pub boost: f32,
 }
 
 impl Configure for OnAction {
@@ -215,75 +445,83 @@ fn perform_action(
     trigger: Trigger<OnAction>,
     mut commands: Commands,
     module_config: ConfigRef<ModuleConfig>,
-    projectile_config: ConfigRef<ProjectileConfig>,
+    effect_handlers: Res<EffectHandlers>,
     player_deck: Res<PlayerDeck>,
-    enemy_deck: Res<EnemyDeck>,
-    game_assets: Res<GameplayAssets>,
-    audio_settings: Res<AudioSettings>,
-    ship_query: Query<(&Children, &Faction, &LinearVelocity)>,
-    children_query: Query<&Children>,
-    weapon_query: Query<&GlobalTransform, With<Weapon>>,
+    enemy_query: Query<&EnemyDeck>,
+    faction_query: Query<&Faction>,
     mut stats: ResMut<Stats>,
 ) {
-    let projectile_config = r!(projectile_config.get());
     let module_config = r!(module_config.get());
     let action = r!(module_config.actions.get(&trigger.action));
 
-    // Choose a weapon on the ship.
-    let rng = &mut thread_rng();
-    let (children, &ship_faction, ship_velocity) = r!(ship_query.get(trigger.source));
-    let mut weapons = Vec::<&_>::new();
-    for &child in children {
-        weapons.extend(weapon_query.get(child));
-        for &child in children_query.get(child).into_iter().flatten() {
-            weapons.extend(weapon_query.get(child));
-        }
-    }
-    let weapon_gt = r!(weapons.choose(rng));
-    let weapon_transform = weapon_gt.compute_transform();
-
     // Determine flux.
-    let is_player = ship_faction == Faction::Player;
-    let flux = match is_player {
-        true => player_deck.flux,
-        false => enemy_deck.flux,
-    };
+    let flux = match r!(faction_query.get(trigger.source)) {
+        Faction::Player => player_deck.flux,
+        Faction::Enemy => r!(enemy_query.get(trigger.source)).flux,
+    } + trigger.boost;
 
-    // Spawn projectile.
-    if let Some(projectile) = projectile_config.projectiles.get(&action.effect_projectile) {
-        commands.spawn((
-            projectile.generate(
-                rng,
-                weapon_transform,
-                ship_velocity.0,
-                ship_faction,
-                trigger.target,
+    // Perform each effect through its handler.
+    for effect in &action.effects {
+        let handler = c!(effect_handlers.get(effect.kind()));
+        commands.run_system_with(
+            handler,
+            EffectInput {
+                effect: effect.clone(),
+                source: trigger.source,
+                target: trigger.target,
                 flux,
-            ),
-            DespawnOnExitState::<Level>::default(),
-        ));
-        if let Some(spawn_sfx) = &projectile.spawn_sfx {
-            commands.spawn((
-                sfx_audio(&audio_settings, spawn_sfx.clone(), 1.0),
-                DespawnOnExitState::<Level>::default(),
-            ));
-        }
+            },
+        );
     }
 
-    // Heal.
-    if action.effect_heal > f32::EPSILON {
-        commands
-            .entity(trigger.source)
-            .trigger(OnHeal(action.effect_heal * flux));
-        commands.spawn((
-            sfx_audio(
-                &audio_settings,
-                game_assets.repair_sfx.clone(),
-                2f32.powf(1.0 / 12.0 * rng.gen_range(0..12) as f32),
+    *stats.actions.entry_ref(&trigger.action).or_default() += 1;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_condition() {
+        let action = |x: &str| Condition::Action(x.to_string());
+        for (case, want) in [
+            ("", Condition::Start),
+            ("  ", Condition::Start),
+            ("missile", action("missile")),
+            (" missile ", action("missile")),
+            ("*", Condition::Any),
+            ("!missile", Condition::Not(Box::new(action("missile")))),
+            (
+                "missile|laser",
+                Condition::OneOf(vec![action("missile"), action("laser")]),
+            ),
+            ("chain>=3", Condition::ChainAtLeast(3.0)),
+            ("chain>= 2.5", Condition::ChainAtLeast(2.5)),
+            ("flux>=4", Condition::FluxAtLeast(4.0)),
+            (
+                "!missile|laser",
+                Condition::Not(Box::new(Condition::OneOf(vec![
+                    action("missile"),
+                    action("laser"),
+                ]))),
             ),
-            DespawnOnExitState::<Level>::default(),
-        ));
+            (
+                "!!*",
+                Condition::Not(Box::new(Condition::Not(Box::new(Condition::Any)))),
+            ),
+            (
+                "chain>=2|flux>=3|*",
+                Condition::OneOf(vec![
+                    Condition::ChainAtLeast(2.0),
+                    Condition::FluxAtLeast(3.0),
+                    Condition::Any,
+                ]),
+            ),
+            ("chain>=x", action("chain>=x")),
+            ("flux>=", action("flux>=")),
+            ("!", Condition::Not(Box::new(Condition::Start))),
+        ] {
+            assert_eq!(Condition::parse(case), want, "{case:?}");
+        }
     }
-
-    *stats.actions.entry_ref(&trigger.action).or_default() += 1;
 }
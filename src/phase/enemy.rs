@@ -1,10 +1,15 @@
 use crate::animation::oscillate::Oscillate;
+use crate::boss::Defeat;
+use crate::boss::EnemyBehavior;
+use crate::combat::damage::OnDamage;
 use crate::combat::death::Dead;
+use crate::combat::health::Health;
+use crate::combat::target::EnemyIndex;
 use crate::core::audio::AudioSettings;
 use crate::core::audio::sfx_audio;
 use crate::deck::EnemyDeck;
 use crate::level::Level;
-use crate::menu::Menu;
+use crate::module::ModuleConfig;
 use crate::module::OnAction;
 use crate::phase::Phase;
 use crate::phase::PhaseConfig;
@@ -13,14 +18,18 @@ use crate::phase::Step;
 use crate::phase::StepTimer;
 use crate::phase::on_step_timer;
 use crate::prelude::*;
+use crate::rng::GameRng;
 use crate::screen::gameplay::GameplayAssets;
 use crate::ship::EnemyShip;
 use crate::ship::PlayerShip;
+use crate::theme::toast::toast;
 
 pub(super) fn plugin(app: &mut App) {
+    app.configure::<EnemyTurns>();
+
     app.add_systems(
         StateFlush,
-        Phase::Enemy.on_enter(reset_step_timer_for_enemy),
+        Phase::Enemy.on_enter((play_enemy_turns, reset_step_timer_for_enemy).chain()),
     );
     app.add_systems(
         Update,
@@ -32,16 +41,82 @@ pub(super) fn plugin(app: &mut App) {
     );
 }
 
+/// The enemy ships taking turns this enemy phase, in order.
+#[derive(Resource, Reflect, Default, Debug)]
+#[reflect(Resource)]
+struct EnemyTurns {
+    order: Vec<Entity>,
+    /// The index of the enemy ship currently taking its turn.
+    idx: usize,
+}
+
+impl Configure for EnemyTurns {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.init_resource::<Self>();
+    }
+}
+
+fn play_enemy_turns(
+    mut commands: Commands,
+    module_config: ConfigRef<ModuleConfig>,
+    round: Res<Round>,
+    mut enemy_turns: ResMut<EnemyTurns>,
+    mut game_rng: ResMut<GameRng>,
+    mut enemy_query: Query<
+        (
+            Entity,
+            &EnemyIndex,
+            &mut EnemyDeck,
+            &mut EnemyBehavior,
+            &Health,
+        ),
+        (With<EnemyShip>, Without<Dead>),
+    >,
+) {
+    let module_config = r!(module_config.get());
+
+    let mut enemies = enemy_query.iter_mut().collect::<Vec<_>>();
+    enemies.sort_by_key(|(_, idx, ..)| **idx);
+    enemy_turns.order = enemies.iter().map(|(entity, ..)| *entity).collect();
+    enemy_turns.idx = 0;
+
+    for (entity, _, mut enemy_deck, mut behavior, health) in enemies {
+        // Enter the next boss phase if enough health has been lost.
+        while let Some(phase) = behavior.update_phase(&mut enemy_deck, health.current) {
+            if !phase.message.is_empty() {
+                commands.spawn((
+                    toast(&phase.message),
+                    DespawnOnExitState::<Level>::default(),
+                ));
+            }
+        }
+        if let Some(enrage) = behavior.enrage_start(round.0) {
+            if !enrage.message.is_empty() {
+                commands.spawn((
+                    toast(&enrage.message),
+                    DespawnOnExitState::<Level>::default(),
+                ));
+            }
+        }
+
+        // Melt down if an overheated module was triggered.
+        let meltdown = enemy_deck.play_turn(module_config, &mut game_rng.enemy);
+        if meltdown > 0.0 {
+            commands.entity(entity).trigger(OnDamage(meltdown));
+        }
+    }
+}
+
 fn reset_step_timer_for_enemy(
     round: Res<Round>,
     phase_config: ConfigRef<PhaseConfig>,
     mut step_timer: ResMut<StepTimer>,
-    enemy_deck: Res<EnemyDeck>,
-    enemy_is_dead: Single<Has<Dead>, With<EnemyShip>>,
+    enemy_query: Query<&EnemyDeck, (With<EnemyShip>, Without<Dead>)>,
 ) {
     let phase_config = r!(phase_config.get());
     step_timer.0 = Timer::from_seconds(
-        if *enemy_is_dead || enemy_deck.is_done(round.0) {
+        if enemy_query.iter().all(|x| x.is_done(round.0)) {
             phase_config.enemy_skip_cooldown
         } else {
             phase_config.enemy_first_cooldown
@@ -53,71 +128,87 @@ fn reset_step_timer_for_enemy(
 fn step_enemy_phase(
     mut commands: Commands,
     phase_config: ConfigRef<PhaseConfig>,
-    level: CurrentRef<Level>,
     round: Res<Round>,
     mut phase: NextMut<Phase>,
     step: Res<Step>,
     mut step_timer: ResMut<StepTimer>,
-    mut enemy_deck: ResMut<EnemyDeck>,
-    mut enemy_ship: Single<
-        (Entity, Has<Dead>, &mut ExternalForce, &mut Oscillate),
+    mut enemy_turns: ResMut<EnemyTurns>,
+    mut enemy_query: Query<
+        (
+            Entity,
+            Has<Dead>,
+            &mut EnemyDeck,
+            &EnemyBehavior,
+            &mut ExternalForce,
+            &mut Oscillate,
+        ),
         With<EnemyShip>,
     >,
     player_ship: Single<Entity, With<PlayerShip>>,
     audio_settings: Res<AudioSettings>,
     game_assets: Res<GameplayAssets>,
-    mut menu: ResMut<NextStateStack<Menu>>,
 ) {
     let phase_config = r!(phase_config.get());
 
     // Explode or flee if dead.
-    if enemy_ship.1 {
-        if level.is_in(&Level(9)) {
+    for (entity, is_dead, _, behavior, mut force, mut oscillate) in &mut enemy_query {
+        cq!(is_dead);
+        if behavior.defeat == Defeat::Explode {
             // Death is ugly. This code is a metaphor for the realities of death.
             let death_oscillate1 = Oscillate::new(vec2(0.0, 0.0), vec2(1.34, 0.0), vec2(1.1, 1.2));
             let death_oscillate2 =
                 Oscillate::new(vec2(2.0, 2.0), vec2(1.34, 0.0), vec2(31.3, 46.7));
-            if enemy_ship.3.rate != death_oscillate1.rate
-                && enemy_ship.3.rate != death_oscillate2.rate
-            {
-                *enemy_ship.3 = death_oscillate1;
+            if oscillate.rate != death_oscillate1.rate && oscillate.rate != death_oscillate2.rate {
+                *oscillate = death_oscillate1;
                 step_timer.0.set_duration(Duration::from_secs_f32(1.0));
-            } else if enemy_ship.3.rate != death_oscillate2.rate {
-                *enemy_ship.3 = death_oscillate2;
+            } else if oscillate.rate != death_oscillate2.rate {
+                *oscillate = death_oscillate2;
                 step_timer.0.set_duration(Duration::from_secs_f32(1.5));
             } else {
-                commands.entity(enemy_ship.0).try_despawn();
+                commands.entity(entity).try_despawn();
                 commands.spawn((
                     sfx_audio(&audio_settings, game_assets.ship_death_sfx.clone(), 1.0),
                     DespawnOnExitState::<Level>::default(),
                 ));
-
-                menu.push(Menu::Victory);
-                menu.acquire();
             }
-        } else {
-            enemy_ship.2.apply_force(phase_config.enemy_escape_force);
-            step_timer.0.set_duration(Duration::from_secs_f32(30.0));
+            return;
         }
+
+        force.set_force(phase_config.enemy_escape_force);
+    }
+
+    // Step the deck of the enemy ship taking its turn.
+    while let Some(&entity) = enemy_turns.order.get(enemy_turns.idx) {
+        let Ok((_, false, mut enemy_deck, behavior, ..)) = enemy_query.get_mut(entity) else {
+            enemy_turns.idx += 1;
+            continue;
+        };
+        let Some(action) = enemy_deck.step(round.0) else {
+            enemy_turns.idx += 1;
+            continue;
+        };
+        commands.trigger(OnAction {
+            action,
+            source: entity,
+            target: *player_ship,
+            boost: enemy_deck.boost() + behavior.enrage_flux(round.0),
+        });
+
+        // Set the next cooldown.
+        let cooldown = Duration::from_secs_f32(if enemy_deck.is_done(round.0) {
+            phase_config.enemy_last_cooldown
+        } else {
+            phase_config.enemy_cooldown.sample_clamped(step.0 as _)
+        });
+        step_timer.0.set_duration(cooldown);
         return;
     }
 
-    // Step the enemy deck.
-    let Some(action) = enemy_deck.step(round.0) else {
-        phase.enter(Phase::Setup);
+    // Wait for defeated enemies to make way for the next wave.
+    if enemy_query.iter().all(|(_, is_dead, ..)| is_dead) {
+        step_timer.0.set_duration(Duration::from_secs_f32(1.0));
         return;
-    };
-    commands.trigger(OnAction {
-        action,
-        source: enemy_ship.0,
-        target: *player_ship,
-    });
-
-    // Set the next cooldown.
-    let cooldown = Duration::from_secs_f32(if enemy_deck.is_done(round.0) {
-        phase_config.enemy_last_cooldown
-    } else {
-        phase_config.enemy_cooldown.sample_clamped(step.0 as _)
-    });
-    step_timer.0.set_duration(cooldown);
+    }
+
+    phase.enter(Phase::Setup);
 }
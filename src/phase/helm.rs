@@ -2,6 +2,8 @@ use crate::combat::death::DieOnExitState;
 use crate::core::audio::AudioSettings;
 use crate::core::audio::sfx_audio;
 use crate::deck::PlayerDeck;
+use crate::hud::reactor::InsertReactorModule;
+use crate::hud::reactor::SwapReactorModules;
 use crate::level::Level;
 use crate::phase::Phase;
 use crate::prelude::*;
@@ -19,7 +21,7 @@ pub(super) fn plugin(app: &mut App) {
             .on_enter(spawn_initial_toast.run_if(Level::is_triggered.and(Level(0).will_enter()))),
     );
 
-    app.configure::<HelmActions>();
+    app.configure::<(HelmActions, HelmHistory)>();
 }
 
 fn spawn_initial_toast(mut commands: Commands) {
@@ -41,13 +43,34 @@ fn helm_is_empty(player_deck: Res<PlayerDeck>) -> bool {
     player_deck.hand.is_empty() && player_deck.storage.is_empty()
 }
 
-#[derive(Actionlike, Reflect, Copy, Clone, Eq, PartialEq, Hash, Debug)]
+#[derive(Actionlike, Reflect, Serialize, Deserialize, Copy, Clone, Eq, PartialEq, Hash, Debug)]
 pub enum HelmActions {
     SelectLeft,
     SelectRight,
     PlayModule,
     DiscardModule,
     EndTurn,
+    SelectSlotPrevious,
+    SelectSlotNext,
+    PlaceModule,
+    SwapSlot,
+    Undo,
+    Redo,
+}
+
+impl HelmActions {
+    /// The actions recorded in replays.
+    ///
+    /// Placing and swapping modules is recorded through the events they trigger instead.
+    pub const RECORDED: [Self; 7] = [
+        Self::SelectLeft,
+        Self::SelectRight,
+        Self::PlayModule,
+        Self::DiscardModule,
+        Self::EndTurn,
+        Self::Undo,
+        Self::Redo,
+    ];
 }
 
 impl Configure for HelmActions {
@@ -69,7 +92,25 @@ impl Configure for HelmActions {
                 .with(Self::DiscardModule, GamepadButton::DPadDown)
                 .with(Self::DiscardModule, KeyCode::KeyS)
                 .with(Self::DiscardModule, KeyCode::ArrowDown)
-                .with(Self::EndTurn, KeyCode::Space),
+                .with(Self::EndTurn, KeyCode::Space)
+                .with(Self::SelectSlotPrevious, GamepadButton::LeftTrigger2)
+                .with(Self::SelectSlotPrevious, KeyCode::KeyQ)
+                .with(Self::SelectSlotNext, GamepadButton::RightTrigger2)
+                .with(Self::SelectSlotNext, KeyCode::KeyE)
+                .with(Self::PlaceModule, GamepadButton::South)
+                .with(Self::PlaceModule, KeyCode::KeyF)
+                .with(Self::SwapSlot, GamepadButton::West)
+                .with(Self::SwapSlot, KeyCode::KeyR)
+                .with(Self::Undo, GamepadButton::North)
+                .with(
+                    Self::Undo,
+                    ButtonlikeChord::modified(ModifierKey::Control, KeyCode::KeyZ),
+                )
+                .with(Self::Redo, GamepadButton::East)
+                .with(
+                    Self::Redo,
+                    ButtonlikeChord::modified(ModifierKey::Control, KeyCode::KeyY),
+                ),
         );
         app.add_plugins(InputManagerPlugin::<Self>::default());
         app.add_systems(Startup, disable_helm_phase_actions);
@@ -98,6 +139,24 @@ impl Configure for HelmActions {
                 helm_end_turn
                     .in_set(UpdateSystems::RecordInput)
                     .run_if(action_just_pressed(Self::EndTurn)),
+                helm_select_slot_previous
+                    .in_set(UpdateSystems::RecordInput)
+                    .run_if(action_just_pressed(Self::SelectSlotPrevious)),
+                helm_select_slot_next
+                    .in_set(UpdateSystems::RecordInput)
+                    .run_if(action_just_pressed(Self::SelectSlotNext)),
+                helm_place_module
+                    .in_set(UpdateSystems::RecordInput)
+                    .run_if(action_just_pressed(Self::PlaceModule)),
+                helm_swap_slot
+                    .in_set(UpdateSystems::RecordInput)
+                    .run_if(action_just_pressed(Self::SwapSlot)),
+                helm_undo
+                    .in_set(UpdateSystems::RecordInput)
+                    .run_if(action_just_pressed(Self::Undo)),
+                helm_redo
+                    .in_set(UpdateSystems::RecordInput)
+                    .run_if(action_just_pressed(Self::Redo)),
             ),
         );
     }
@@ -107,12 +166,20 @@ fn disable_helm_phase_actions(mut helm_actions: ResMut<ActionState<HelmActions>>
     helm_actions.disable_action(&HelmActions::PlayModule);
     helm_actions.disable_action(&HelmActions::DiscardModule);
     helm_actions.disable_action(&HelmActions::EndTurn);
+    helm_actions.disable_action(&HelmActions::PlaceModule);
+    helm_actions.disable_action(&HelmActions::SwapSlot);
+    helm_actions.disable_action(&HelmActions::Undo);
+    helm_actions.disable_action(&HelmActions::Redo);
 }
 
 fn enable_helm_phase_actions(mut helm_actions: ResMut<ActionState<HelmActions>>) {
     helm_actions.enable_action(&HelmActions::PlayModule);
     helm_actions.enable_action(&HelmActions::DiscardModule);
     helm_actions.enable_action(&HelmActions::EndTurn);
+    helm_actions.enable_action(&HelmActions::PlaceModule);
+    helm_actions.enable_action(&HelmActions::SwapSlot);
+    helm_actions.enable_action(&HelmActions::Undo);
+    helm_actions.enable_action(&HelmActions::Redo);
 }
 
 fn disable_helm_actions(mut helm_actions: ResMut<ActionState<HelmActions>>) {
@@ -161,9 +228,12 @@ fn helm_play_module(
     game_assets: Res<GameplayAssets>,
     audio_settings: Res<AudioSettings>,
     mut player_deck: ResMut<PlayerDeck>,
+    mut helm_history: ResMut<HelmHistory>,
 ) {
     rq!(!player_deck.hand.is_empty());
-    if !player_deck.bypass_change_detection().play_selected() && level.is_in(&Level(0)) {
+    let snapshot = player_deck.clone();
+    let played = player_deck.bypass_change_detection().play_selected();
+    if !played && level.is_in(&Level(0)) {
         commands.spawn((
             toast(
                 "[b]The reactor is full.[r]\n\
@@ -175,6 +245,9 @@ fn helm_play_module(
         return;
     }
     player_deck.set_changed();
+    if played {
+        helm_history.record(snapshot);
+    }
 
     if player_deck.hand.is_empty() && level.is_in(&Level(0)) {
         commands.spawn((
@@ -199,10 +272,13 @@ fn helm_discard_module(
     game_assets: Res<GameplayAssets>,
     audio_settings: Res<AudioSettings>,
     mut player_deck: ResMut<PlayerDeck>,
+    mut helm_history: ResMut<HelmHistory>,
 ) {
+    let snapshot = player_deck.clone();
     rq!(player_deck.bypass_change_detection().discard_selected());
     player_deck.set_changed();
     player_deck.last_touched_idx = None;
+    helm_history.record(snapshot);
 
     if player_deck.hand.is_empty() && level.is_in(&Level(0)) {
         commands.spawn((
@@ -224,3 +300,119 @@ fn helm_discard_module(
 fn helm_end_turn(mut phase: NextMut<Phase>) {
     phase.enter(Phase::Reactor);
 }
+
+fn helm_select_slot_previous(
+    mut commands: Commands,
+    audio_settings: Res<AudioSettings>,
+    game_assets: Res<GameplayAssets>,
+    mut player_deck: ResMut<PlayerDeck>,
+) {
+    let old_slot_idx = player_deck.slot_idx;
+    player_deck.bypass_change_detection().advance_slot(-1);
+    if player_deck.slot_idx != old_slot_idx {
+        commands.spawn((
+            sfx_audio(&audio_settings, game_assets.module_hover_sfx.clone(), 1.0),
+            DespawnOnExitState::<Level>::default(),
+        ));
+    }
+}
+
+fn helm_select_slot_next(
+    mut commands: Commands,
+    audio_settings: Res<AudioSettings>,
+    game_assets: Res<GameplayAssets>,
+    mut player_deck: ResMut<PlayerDeck>,
+) {
+    let old_slot_idx = player_deck.slot_idx;
+    player_deck.bypass_change_detection().advance_slot(1);
+    if player_deck.slot_idx != old_slot_idx {
+        commands.spawn((
+            sfx_audio(&audio_settings, game_assets.module_hover_sfx.clone(), 1.0),
+            DespawnOnExitState::<Level>::default(),
+        ));
+    }
+}
+
+fn helm_place_module(mut commands: Commands, player_deck: Res<PlayerDeck>) {
+    rq!(!player_deck.hand.is_empty());
+    commands.trigger(InsertReactorModule {
+        hand_idx: player_deck.hand_idx,
+        slot_idx: player_deck.slot_idx,
+    });
+}
+
+fn helm_swap_slot(mut commands: Commands, mut player_deck: ResMut<PlayerDeck>) {
+    let slot_idx = player_deck.slot_idx;
+    match player_deck.held_slot {
+        // Pick up the selected slot, or put it back down.
+        None => player_deck.bypass_change_detection().held_slot = Some(slot_idx),
+        Some(held) if held == slot_idx => player_deck.bypass_change_detection().held_slot = None,
+        Some(held) => commands.trigger(SwapReactorModules(held, slot_idx)),
+    }
+}
+
+fn helm_undo(
+    mut commands: Commands,
+    audio_settings: Res<AudioSettings>,
+    game_assets: Res<GameplayAssets>,
+    mut player_deck: ResMut<PlayerDeck>,
+    mut helm_history: ResMut<HelmHistory>,
+) {
+    let snapshot = rq!(helm_history.undo.pop());
+    let current = core::mem::replace(&mut *player_deck, snapshot);
+    helm_history.redo.push(current);
+
+    commands.spawn((
+        sfx_audio(&audio_settings, game_assets.module_insert_sfx.clone(), 1.0),
+        DespawnOnExitState::<Level>::default(),
+    ));
+}
+
+fn helm_redo(
+    mut commands: Commands,
+    audio_settings: Res<AudioSettings>,
+    game_assets: Res<GameplayAssets>,
+    mut player_deck: ResMut<PlayerDeck>,
+    mut helm_history: ResMut<HelmHistory>,
+) {
+    let snapshot = rq!(helm_history.redo.pop());
+    let current = core::mem::replace(&mut *player_deck, snapshot);
+    helm_history.undo.push(current);
+
+    commands.spawn((
+        sfx_audio(&audio_settings, game_assets.module_insert_sfx.clone(), 1.0),
+        DespawnOnExitState::<Level>::default(),
+    ));
+}
+
+/// Snapshots of the player deck before and after each decision in the current helm phase.
+///
+/// Decisions in the helm phase never draw modules, so restoring a snapshot can't reroll a draw.
+#[derive(Resource, Default, Debug)]
+pub struct HelmHistory {
+    undo: Vec<PlayerDeck>,
+    redo: Vec<PlayerDeck>,
+}
+
+impl Configure for HelmHistory {
+    fn configure(app: &mut App) {
+        app.init_resource::<Self>();
+        app.add_systems(
+            StateFlush,
+            Phase::Helm.on_edge(clear_helm_history, clear_helm_history),
+        );
+    }
+}
+
+impl HelmHistory {
+    /// Record the player deck from before a decision, clearing the redo stack.
+    pub fn record(&mut self, snapshot: PlayerDeck) {
+        self.undo.push(snapshot);
+        self.redo.clear();
+    }
+}
+
+fn clear_helm_history(mut helm_history: ResMut<HelmHistory>) {
+    helm_history.undo.clear();
+    helm_history.redo.clear();
+}
@@ -45,7 +45,7 @@ impl Config for PhaseConfig {
     const FILE: &'static str = "phase.ron";
 }
 
-#[derive(State, Reflect, Copy, Clone, Default, Eq, PartialEq, Debug)]
+#[derive(State, Reflect, Serialize, Deserialize, Copy, Clone, Default, Eq, PartialEq, Debug)]
 #[state(log_flush, react, after(Level))]
 #[reflect(Resource)]
 pub enum Phase {
@@ -96,7 +96,7 @@ fn play_phase_change_sfx(
 
 #[derive(Resource, Reflect, Default, Debug)]
 #[reflect(Resource)]
-struct Round(usize);
+pub struct Round(pub usize);
 
 impl Configure for Round {
     fn configure(app: &mut App) {
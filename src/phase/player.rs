@@ -1,3 +1,6 @@
+use crate::combat::death::Dead;
+use crate::combat::target::EnemyIndex;
+use crate::combat::target::PlayerTarget;
 use crate::deck::PlayerDeck;
 use crate::module::OnAction;
 use crate::phase::Phase;
@@ -48,19 +51,25 @@ fn step_player_phase(
     mut step_timer: ResMut<StepTimer>,
     mut player_deck: ResMut<PlayerDeck>,
     player_ship: Single<Entity, With<PlayerShip>>,
-    enemy_ship: Single<Entity, With<EnemyShip>>,
+    player_target: Res<PlayerTarget>,
+    enemy_query: Query<(Entity, &EnemyIndex, Has<Dead>), With<EnemyShip>>,
 ) {
     let phase_config = r!(phase_config.get());
+    let target = r!(player_target.find(&enemy_query));
 
     // Step through the player reactor chain.
     let Some(action) = player_deck.step_player() else {
         phase.enter(Phase::Enemy);
         return;
     };
+    let boost = player_deck
+        .last_touched_idx
+        .map_or(0.0, |idx| player_deck.reactor[idx].boost);
     commands.trigger(OnAction {
         action,
         source: *player_ship,
-        target: *enemy_ship,
+        target,
+        boost,
     });
 
     // Set the next cooldown.
@@ -1,3 +1,4 @@
+use crate::combat::damage::OnDamage;
 use crate::core::audio::AudioSettings;
 use crate::core::audio::sfx_audio;
 use crate::deck::PlayerDeck;
@@ -9,6 +10,7 @@ use crate::phase::StepTimer;
 use crate::phase::on_step_timer;
 use crate::prelude::*;
 use crate::screen::gameplay::GameplayAssets;
+use crate::ship::PlayerShip;
 use crate::stats::Stats;
 
 pub(super) fn plugin(app: &mut App) {
@@ -28,12 +30,14 @@ pub(super) fn plugin(app: &mut App) {
 
 fn reset_step_timer_for_power_up(
     phase_config: ConfigRef<PhaseConfig>,
+    module_config: ConfigRef<ModuleConfig>,
     mut step_timer: ResMut<StepTimer>,
     player_deck: Res<PlayerDeck>,
 ) {
     let phase_config = r!(phase_config.get());
+    let module_config = r!(module_config.get());
     step_timer.0 = Timer::from_seconds(
-        if player_deck.is_reactor_done() {
+        if player_deck.is_reactor_done(module_config) {
             phase_config.reactor_skip_cooldown
         } else {
             phase_config.reactor_first_cooldown
@@ -51,6 +55,7 @@ fn step_power_up_phase(
     mut phase: NextMut<Phase>,
     mut step_timer: ResMut<StepTimer>,
     mut player_deck: ResMut<PlayerDeck>,
+    player_ship: Single<Entity, With<PlayerShip>>,
     mut stats: ResMut<Stats>,
 ) {
     let phase_config = r!(phase_config.get());
@@ -70,19 +75,30 @@ fn step_power_up_phase(
         DespawnOnExitState::<Level>::default(),
     ));
 
+    // Melt down if an overheated module was triggered.
+    if player_deck.meltdown > 0.0 {
+        stats.damage_taken += player_deck.meltdown;
+        commands
+            .entity(*player_ship)
+            .trigger(OnDamage(player_deck.meltdown));
+        player_deck.meltdown = 0.0;
+    }
+
     // Record max flux.
     stats.highest_flux = stats.highest_flux.max(player_deck.flux);
 
     // Set the next cooldown.
-    let cooldown = Duration::from_secs_f32(if let Some(idx) = player_deck.next_matching_module() {
-        let chain = if player_deck.reactor[idx].condition.is_empty() {
-            0.0
+    let cooldown = Duration::from_secs_f32(
+        if let Some(idx) = player_deck.next_matching_module(module_config) {
+            let chain = if player_deck.reactor[idx].condition.is_empty() {
+                0.0
+            } else {
+                player_deck.chain
+            };
+            phase_config.reactor_cooldown.sample_clamped(chain)
         } else {
-            player_deck.chain
-        };
-        phase_config.reactor_cooldown.sample_clamped(chain)
-    } else {
-        phase_config.reactor_last_cooldown
-    });
+            phase_config.reactor_last_cooldown
+        },
+    );
     step_timer.0.set_duration(cooldown);
 }
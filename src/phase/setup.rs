@@ -5,19 +5,21 @@ use crate::core::audio::sfx_audio;
 use crate::deck::PlayerDeck;
 use crate::level::Level;
 use crate::menu::Menu;
+use crate::module::ModuleConfig;
 use crate::phase::Phase;
 use crate::phase::PhaseConfig;
 use crate::phase::Step;
 use crate::phase::StepTimer;
 use crate::phase::on_step_timer;
 use crate::prelude::*;
+use crate::rng::GameRng;
 use crate::screen::gameplay::GameplayAssets;
 use crate::ship::PlayerShip;
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(
         StateFlush,
-        Phase::Setup.on_enter(reset_step_timer_for_setup),
+        Phase::Setup.on_enter((dissipate_reactor_heat, reset_step_timer_for_setup)),
     );
     app.add_systems(
         Update,
@@ -29,6 +31,14 @@ pub(super) fn plugin(app: &mut App) {
     );
 }
 
+fn dissipate_reactor_heat(
+    module_config: ConfigRef<ModuleConfig>,
+    mut player_deck: ResMut<PlayerDeck>,
+) {
+    let module_config = r!(module_config.get());
+    player_deck.dissipate_heat(module_config);
+}
+
 fn reset_step_timer_for_setup(
     phase_config: ConfigRef<PhaseConfig>,
     mut step_timer: ResMut<StepTimer>,
@@ -52,6 +62,7 @@ fn step_setup_phase(
     step: Res<Step>,
     mut step_timer: ResMut<StepTimer>,
     mut player_deck: ResMut<PlayerDeck>,
+    mut game_rng: ResMut<GameRng>,
     mut player_ship: Single<(Entity, Has<Dead>, &mut Oscillate), With<PlayerShip>>,
     audio_settings: Res<AudioSettings>,
     game_assets: Res<GameplayAssets>,
@@ -87,7 +98,7 @@ fn step_setup_phase(
     }
 
     // Step the setup.
-    if !player_deck.step_setup(&mut thread_rng()) {
+    if !player_deck.step_setup(&mut game_rng.deck) {
         phase.enter(Phase::Helm);
         return;
     }
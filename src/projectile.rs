@@ -2,6 +2,8 @@ use crate::animation::oscillate::Oscillate;
 use crate::combat::damage::Damage;
 use crate::combat::faction::Faction;
 use crate::core::physics::GameLayer;
+use crate::effect::ActionEffect;
+use crate::module::ActionInfo;
 use crate::prelude::*;
 
 pub(super) fn plugin(app: &mut App) {
@@ -39,6 +41,28 @@ impl Config for ProjectileConfig {
     }
 }
 
+impl ProjectileConfig {
+    /// Estimate the damage an action deals at the given flux, before shields and armor.
+    ///
+    /// Damage over time is counted up front.
+    pub fn action_damage(&self, action: &ActionInfo, flux: f32) -> f32 {
+        action
+            .effects
+            .iter()
+            .map(|effect| match effect {
+                ActionEffect::Projectile(key) => self
+                    .projectiles
+                    .get(key)
+                    .map_or(0.0, |projectile| projectile.damage * flux),
+                ActionEffect::DamageOverTime { damage, rounds } => {
+                    damage.max(0.0) * flux * *rounds as f32
+                },
+                _ => 0.0,
+            })
+            .sum()
+    }
+}
+
 #[derive(Reflect, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct ProjectileInfo {
@@ -99,6 +123,7 @@ impl ProjectileInfo {
         faction: Faction,
         target: Entity,
         flux: f32,
+        speed_scale: f32,
     ) -> impl Bundle {
         // Calculate initial direction.
         let angle = transform.rotation.to_degrees()
@@ -119,9 +144,9 @@ impl ProjectileInfo {
         transform.scale *= self.scale.extend(1.0);
 
         // Calculate initial velocity and acceleration.
-        let speed = self.speed + self.speed_spread * rng.gen_range(-1.0..=1.0);
+        let speed = speed_scale * (self.speed + self.speed_spread * rng.gen_range(-1.0..=1.0));
         let velocity = velocity + speed * direction;
-        let acceleration = direction.rotate(self.acceleration);
+        let acceleration = speed_scale * direction.rotate(self.acceleration);
 
         // Calculate homing target position offset.
         let offset =
@@ -154,7 +179,9 @@ impl ProjectileInfo {
             RotateWithThruster(self.acceleration_rotation),
             (
                 LinearVelocity(velocity),
-                MaxLinearSpeed(self.speed_max * self.speed_max_flux_factor.powf(flux - 1.0)),
+                MaxLinearSpeed(
+                    speed_scale * self.speed_max * self.speed_max_flux_factor.powf(flux - 1.0),
+                ),
                 RigidBody::Dynamic,
                 Mass(1.0),
                 ExternalForce::ZERO.with_persistence(false),
@@ -0,0 +1,293 @@
+use crate::combat::damage::OnDamage;
+use crate::combat::death::Dead;
+use crate::combat::death::OnDeath;
+use crate::combat::faction::Faction;
+use crate::combat::health::OnHeal;
+use crate::deck::PlayerDeck;
+use crate::level::Level;
+use crate::level::spawn_level;
+use crate::module::Condition;
+use crate::module::ModuleConfig;
+use crate::module::OnAction;
+use crate::phase::Phase;
+use crate::prelude::*;
+use crate::rng::GameRng;
+use crate::save::Resume;
+use crate::ship::EnemyShip;
+use crate::ship::PlayerShip;
+use crate::stats::Stats;
+
+pub(super) fn plugin(app: &mut App) {
+    app.configure::<(ConfigHandle<RelicConfig>, TriggerRelics)>();
+
+    app.add_systems(
+        StateFlush,
+        (
+            Level::ANY.on_enter(trigger_level_start_relics.after(spawn_level)),
+            Phase::ANY.on_enter(trigger_phase_start_relics),
+            Phase::ANY
+                .on_exit(trigger_phase_end_relics)
+                .run_if(not(Level::is_triggered)),
+        ),
+    );
+    app.add_observer(trigger_action_relics);
+    app.add_observer(trigger_damage_relics);
+    app.add_observer(trigger_heal_relics);
+    app.add_observer(trigger_death_relics);
+}
+
+#[derive(Asset, Reflect, Serialize, Deserialize, Default)]
+#[serde(deny_unknown_fields, default)]
+pub struct RelicConfig {
+    pub relics: HashMap<String, RelicInfo>,
+}
+
+impl Config for RelicConfig {
+    const FILE: &'static str = "relic.ron";
+
+    fn on_load(&mut self, world: &mut World) {
+        let asset_server = world.resource::<AssetServer>();
+        for relic in self.relics.values_mut() {
+            relic.icon = asset_server.load(&relic.icon_path);
+        }
+    }
+
+    fn count_progress(&self, asset_server: &AssetServer) -> Progress {
+        let mut progress = true.into();
+        for relic in self.relics.values() {
+            progress += asset_server.is_loaded_with_dependencies(&relic.icon).into();
+        }
+        progress
+    }
+}
+
+/// A run-long passive item that reacts to events in combat.
+#[derive(Reflect, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RelicInfo {
+    pub name: String,
+    #[serde(rename = "icon")]
+    pub icon_path: String,
+    #[serde(skip)]
+    pub icon: Handle<Image>,
+    /// Flavor text shown above the relic's triggers.
+    #[serde(default)]
+    pub description: String,
+    pub triggers: Vec<RelicTrigger>,
+}
+
+impl RelicInfo {
+    /// Rich text describing the relic for a tooltip.
+    pub fn description(&self, module_config: &ModuleConfig) -> String {
+        let mut lines = vec![format!("[b]{}[r]", self.name)];
+        if !self.description.is_empty() {
+            lines.push(String::new());
+            lines.push(self.description.clone());
+        }
+        lines.push(String::new());
+        for trigger in &self.triggers {
+            lines.push(format!(
+                "{}{}",
+                trigger.on.description(module_config),
+                trigger.effect.description(),
+            ));
+        }
+        lines.join("\n")
+    }
+
+    /// Whether any of the relic's triggers react to this event.
+    pub fn reacts_to(&self, event: &RelicEvent, player_deck: &PlayerDeck) -> bool {
+        self.triggers
+            .iter()
+            .any(|x| x.on.matches(event, player_deck))
+    }
+}
+
+#[derive(Reflect, Serialize, Deserialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct RelicTrigger {
+    pub on: RelicEvent,
+    pub effect: RelicEffect,
+}
+
+/// Something that happens in combat that relics can react to.
+#[derive(Reflect, Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub enum RelicEvent {
+    /// The player performs an action that meets this module condition.
+    Action(String),
+    /// The player ship takes damage.
+    Damaged,
+    /// The player ship is repaired.
+    Healed,
+    /// An enemy ship is destroyed.
+    EnemyDeath,
+    PhaseStart(Phase),
+    PhaseEnd(Phase),
+    LevelStart,
+}
+
+impl RelicEvent {
+    fn description(&self, module_config: &ModuleConfig) -> String {
+        let phase_name = |phase: &Phase| format!("{phase:?}").to_lowercase();
+        match self {
+            Self::Action(condition) => Condition::parse(condition).description(module_config),
+            Self::Damaged => "When your hull takes damage, ".to_string(),
+            Self::Healed => "When your hull is repaired, ".to_string(),
+            Self::EnemyDeath => "When an enemy is destroyed, ".to_string(),
+            Self::PhaseStart(phase) => {
+                format!("At the start of each {} phase, ", phase_name(phase))
+            },
+            Self::PhaseEnd(phase) => format!("At the end of each {} phase, ", phase_name(phase)),
+            Self::LevelStart => "At the start of each star, ".to_string(),
+        }
+    }
+
+    fn matches(&self, event: &Self, player_deck: &PlayerDeck) -> bool {
+        match (self, event) {
+            (Self::Action(condition), Self::Action(action)) => {
+                Condition::parse(condition).is_met(action, player_deck.chain, player_deck.flux)
+            },
+            _ => self == event,
+        }
+    }
+}
+
+#[derive(Reflect, Serialize, Deserialize, Clone, Debug)]
+pub enum RelicEffect {
+    /// Raise the player's flux by this amount.
+    AddFlux(f32),
+    /// Remove this much heat from every module in the reactor.
+    Cool(f32),
+    /// Damage every enemy by this amount.
+    Damage(f32),
+    /// Draw this many modules from storage to hand.
+    Draw(usize),
+}
+
+impl RelicEffect {
+    fn description(&self) -> String {
+        match self {
+            Self::AddFlux(x) => format!("gain {x} flux."),
+            Self::Cool(x) => format!("cool every reactor module by {x}."),
+            Self::Damage(x) => format!("deal {x} damage to every enemy."),
+            Self::Draw(1) => "draw a module.".to_string(),
+            Self::Draw(x) => format!("draw {x} modules."),
+        }
+    }
+}
+
+/// Perform the effects of the player's relics that react to this event.
+#[derive(Event, Reflect, Debug)]
+pub struct TriggerRelics(pub RelicEvent);
+
+impl Configure for TriggerRelics {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_observer(apply_relic_effects);
+    }
+}
+
+fn apply_relic_effects(
+    trigger: Trigger<TriggerRelics>,
+    mut commands: Commands,
+    relic_config: ConfigRef<RelicConfig>,
+    enemy_query: Query<Entity, (With<EnemyShip>, Without<Dead>)>,
+    mut player_deck: ResMut<PlayerDeck>,
+    mut stats: ResMut<Stats>,
+    mut game_rng: ResMut<GameRng>,
+) {
+    let relic_config = r!(relic_config.get());
+
+    let effects = player_deck
+        .relics
+        .iter()
+        .filter_map(|key| relic_config.relics.get(key))
+        .flat_map(|relic| &relic.triggers)
+        .filter(|x| x.on.matches(&trigger.0, &player_deck))
+        .map(|x| x.effect.clone())
+        .collect::<Vec<_>>();
+
+    for effect in effects {
+        match effect {
+            RelicEffect::AddFlux(x) => {
+                // Keep the bonus when the reactor chain raises flux later in the phase.
+                player_deck.bonus_flux += x;
+                player_deck.flux = (player_deck.flux + x).max(0.0);
+            },
+            RelicEffect::Cool(x) => {
+                for idx in 0..player_deck.reactor.len() {
+                    player_deck.cool_slot(idx, x);
+                }
+            },
+            RelicEffect::Damage(x) => {
+                for enemy in &enemy_query {
+                    commands.entity(enemy).trigger(OnDamage(x));
+                    stats.damage_given += x;
+                }
+            },
+            RelicEffect::Draw(x) => {
+                for _ in 0..x {
+                    player_deck.draw_random(&mut game_rng.deck);
+                }
+            },
+        }
+    }
+}
+
+fn trigger_level_start_relics(mut commands: Commands, resume: Option<Res<Resume>>) {
+    // The level already started before the save.
+    rq!(resume.is_none_or(|x| x.0.round == 0));
+    commands.trigger(TriggerRelics(RelicEvent::LevelStart));
+}
+
+fn trigger_phase_start_relics(mut commands: Commands, phase: NextRef<Phase>) {
+    let phase = *r!(phase.get());
+    commands.trigger(TriggerRelics(RelicEvent::PhaseStart(phase)));
+}
+
+fn trigger_phase_end_relics(mut commands: Commands, phase: CurrentRef<Phase>) {
+    let phase = *r!(phase.get());
+    commands.trigger(TriggerRelics(RelicEvent::PhaseEnd(phase)));
+}
+
+fn trigger_action_relics(
+    trigger: Trigger<OnAction>,
+    mut commands: Commands,
+    faction_query: Query<&Faction>,
+) {
+    rq!(matches!(
+        r!(faction_query.get(trigger.source)),
+        Faction::Player
+    ));
+    commands.trigger(TriggerRelics(RelicEvent::Action(trigger.action.clone())));
+}
+
+fn trigger_damage_relics(
+    trigger: Trigger<OnDamage>,
+    mut commands: Commands,
+    player_query: Query<(), With<PlayerShip>>,
+) {
+    let target = r!(trigger.get_target());
+    rq!(player_query.contains(target) && trigger.0 > f32::EPSILON);
+    commands.trigger(TriggerRelics(RelicEvent::Damaged));
+}
+
+fn trigger_heal_relics(
+    trigger: Trigger<OnHeal>,
+    mut commands: Commands,
+    player_query: Query<(), With<PlayerShip>>,
+) {
+    let target = r!(trigger.get_target());
+    rq!(player_query.contains(target) && trigger.0 > f32::EPSILON);
+    commands.trigger(TriggerRelics(RelicEvent::Healed));
+}
+
+fn trigger_death_relics(
+    trigger: Trigger<OnDeath>,
+    mut commands: Commands,
+    enemy_query: Query<(), With<EnemyShip>>,
+) {
+    let target = rq!(trigger.get_target());
+    rq!(enemy_query.contains(target));
+    commands.trigger(TriggerRelics(RelicEvent::EnemyDeath));
+}
@@ -0,0 +1,433 @@
+use crate::combat::target::SelectTarget;
+use crate::deck::PlayerDeck;
+use crate::deck::SelectedDeck;
+use crate::endless::EndlessMode;
+use crate::hud::reactor::InsertReactorModule;
+use crate::hud::reactor::RemoveReactorModule;
+use crate::hud::reactor::SwapReactorModules;
+use crate::level::Level;
+use crate::menu::Menu;
+use crate::menu::shop::BuyShopOffer;
+use crate::menu::shop::LeaveShop;
+use crate::menu::shop::RepairHull;
+use crate::menu::shop::ScrapModule;
+use crate::menu::upgrade::PickUpgrades;
+use crate::phase::Phase;
+use crate::phase::Round;
+use crate::phase::helm::HelmActions;
+use crate::prelude::*;
+use crate::rng::GameRng;
+use crate::save::Resume;
+use crate::screen::Screen;
+use crate::util::storage;
+
+pub(super) fn plugin(app: &mut App) {
+    app.configure::<(Replay, Playback)>();
+}
+
+/// A recording of every player decision in a run.
+///
+/// Together with the run seed, this is enough to replay the run identically.
+#[derive(Resource, Serialize, Deserialize, Default, Clone, Debug)]
+#[serde(default)]
+pub struct Replay {
+    pub version: u32,
+    pub seed: u64,
+    /// The index of the player deck in the deck config.
+    pub deck: usize,
+    /// Whether the run continues past the authored levels.
+    pub endless: bool,
+    pub inputs: Vec<ReplayInput>,
+}
+
+impl Configure for Replay {
+    fn configure(app: &mut App) {
+        app.init_resource::<Self>();
+        app.add_systems(
+            StateFlush,
+            (
+                Screen::Gameplay.on_enter(restore_replay.run_if(resource_exists::<Resume>)),
+                Level(0).on_enter(
+                    reset_replay
+                        .run_if(not(resource_exists::<Resume>))
+                        .run_if(not(resource_exists::<Playback>)),
+                ),
+                Level::ANY.on_enter(drop_retried_inputs),
+            ),
+        );
+        app.add_systems(
+            Update,
+            (
+                Phase::Helm.on_update(
+                    record_helm_actions
+                        .in_set(UpdateSystems::Update)
+                        .after(play_replay),
+                ),
+                Screen::Gameplay.on_update(
+                    store_replay
+                        .in_set(UpdateSystems::SyncLate)
+                        .run_if(resource_changed::<Self>)
+                        .run_if(not(resource_exists::<Playback>)),
+                ),
+            ),
+        );
+        app.add_observer(record_remove_reactor_module);
+        app.add_observer(record_insert_reactor_module);
+        app.add_observer(record_swap_reactor_modules);
+        app.add_observer(record_select_target);
+        app.add_observer(record_pick_upgrades);
+        app.add_observer(record_buy_shop_offer);
+        app.add_observer(record_scrap_module);
+        app.add_observer(record_repair_hull);
+        app.add_observer(record_leave_shop);
+    }
+}
+
+impl Replay {
+    const FILE: &'static str = "replay.ron";
+    /// Increment this when a change would break old replay files.
+    const VERSION: u32 = 2;
+
+    /// Load the replay file if it exists and is compatible with this version.
+    pub fn load() -> Option<Self> {
+        let replay = ron::from_str::<Self>(&storage::read(Self::FILE)?).ok()?;
+        if replay.version != Self::VERSION {
+            warn!(
+                "Ignoring replay file with version {} (expected {})",
+                replay.version,
+                Self::VERSION,
+            );
+            return None;
+        }
+
+        Some(replay)
+    }
+
+    pub fn exists() -> bool {
+        Self::load().is_some()
+    }
+
+    fn store(&self) {
+        let text = r!(ron::to_string(self));
+        storage::write(Self::FILE, &text);
+    }
+
+    fn record(&mut self, level: usize, round: usize, decision: Decision) {
+        self.inputs.push(ReplayInput {
+            level,
+            round,
+            decision,
+        });
+    }
+}
+
+/// A player decision and when it was made.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ReplayInput {
+    pub level: usize,
+    pub round: usize,
+    pub decision: Decision,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub enum Decision {
+    /// A helm action, with the hand index that was selected when it was pressed.
+    Helm(HelmActions, usize),
+    /// Remove the module at this reactor index to storage.
+    RemoveModule(usize),
+    /// Insert the module at this hand index into this reactor index.
+    InsertModule(usize, usize),
+    /// Swap the modules at these reactor indices.
+    SwapModules(usize, usize),
+    /// Target the enemy ship at this index.
+    SelectTarget(usize),
+    /// Pick the offered upgrades at these indices.
+    PickUpgrades(Vec<usize>),
+    /// Buy the shop offer at this index.
+    BuyShopOffer(usize),
+    /// Scrap the module at this storage index.
+    ScrapModule(usize),
+    RepairHull,
+    LeaveShop,
+}
+
+fn reset_replay(
+    selected_deck: Res<SelectedDeck>,
+    endless: Res<EndlessMode>,
+    mut replay: ResMut<Replay>,
+) {
+    *replay = Replay {
+        version: Replay::VERSION,
+        deck: selected_deck.0,
+        endless: endless.0,
+        ..default()
+    };
+}
+
+fn restore_replay(resume: Res<Resume>, mut replay: ResMut<Replay>) {
+    let save = &resume.0;
+    *replay = Replay::load()
+        .filter(|x| x.seed == save.seed)
+        .unwrap_or(Replay {
+            version: Replay::VERSION,
+            endless: save.endless,
+            ..default()
+        });
+
+    // Drop decisions made after the save, since they will be made again.
+    replay
+        .inputs
+        .retain(|x| (x.level, x.round) < (save.level, save.round));
+}
+
+/// Drop the decisions of a level that is being retried, since they will be made again.
+fn drop_retried_inputs(
+    current_level: CurrentRef<Level>,
+    next_level: NextRef<Level>,
+    mut replay: ResMut<Replay>,
+) {
+    let level = rq!(current_level.get()).0;
+    rq!(next_level.get() == Some(&Level(level)));
+    replay.inputs.retain(|x| x.level != level);
+}
+
+fn store_replay(game_rng: Res<GameRng>, mut replay: ResMut<Replay>) {
+    replay.bypass_change_detection().seed = game_rng.seed;
+    replay.store();
+}
+
+fn record_helm_actions(
+    level: CurrentRef<Level>,
+    round: Res<Round>,
+    player_deck: Res<PlayerDeck>,
+    helm_actions: Res<ActionState<HelmActions>>,
+    mut replay: ResMut<Replay>,
+) {
+    let level = r!(level.get()).0;
+    for action in HelmActions::RECORDED {
+        cq!(helm_actions.just_pressed(&action));
+        replay.record(level, round.0, Decision::Helm(action, player_deck.hand_idx));
+    }
+}
+
+fn record_remove_reactor_module(
+    trigger: Trigger<RemoveReactorModule>,
+    level: CurrentRef<Level>,
+    round: Res<Round>,
+    mut replay: ResMut<Replay>,
+) {
+    let level = r!(level.get()).0;
+    replay.record(level, round.0, Decision::RemoveModule(trigger.0));
+}
+
+fn record_insert_reactor_module(
+    trigger: Trigger<InsertReactorModule>,
+    level: CurrentRef<Level>,
+    round: Res<Round>,
+    mut replay: ResMut<Replay>,
+) {
+    let level = r!(level.get()).0;
+    replay.record(
+        level,
+        round.0,
+        Decision::InsertModule(trigger.hand_idx, trigger.slot_idx),
+    );
+}
+
+fn record_swap_reactor_modules(
+    trigger: Trigger<SwapReactorModules>,
+    level: CurrentRef<Level>,
+    round: Res<Round>,
+    mut replay: ResMut<Replay>,
+) {
+    let level = r!(level.get()).0;
+    replay.record(level, round.0, Decision::SwapModules(trigger.0, trigger.1));
+}
+
+fn record_select_target(
+    trigger: Trigger<SelectTarget>,
+    level: CurrentRef<Level>,
+    round: Res<Round>,
+    mut replay: ResMut<Replay>,
+) {
+    let level = r!(level.get()).0;
+    replay.record(level, round.0, Decision::SelectTarget(trigger.0));
+}
+
+fn record_pick_upgrades(
+    trigger: Trigger<PickUpgrades>,
+    level: CurrentRef<Level>,
+    round: Res<Round>,
+    mut replay: ResMut<Replay>,
+) {
+    let level = r!(level.get()).0;
+    replay.record(level, round.0, Decision::PickUpgrades(trigger.0.clone()));
+}
+
+fn record_buy_shop_offer(
+    trigger: Trigger<BuyShopOffer>,
+    level: CurrentRef<Level>,
+    round: Res<Round>,
+    mut replay: ResMut<Replay>,
+) {
+    let level = r!(level.get()).0;
+    replay.record(level, round.0, Decision::BuyShopOffer(trigger.0));
+}
+
+fn record_scrap_module(
+    trigger: Trigger<ScrapModule>,
+    level: CurrentRef<Level>,
+    round: Res<Round>,
+    mut replay: ResMut<Replay>,
+) {
+    let level = r!(level.get()).0;
+    replay.record(level, round.0, Decision::ScrapModule(trigger.0));
+}
+
+fn record_repair_hull(
+    _: Trigger<RepairHull>,
+    level: CurrentRef<Level>,
+    round: Res<Round>,
+    mut replay: ResMut<Replay>,
+) {
+    let level = r!(level.get()).0;
+    replay.record(level, round.0, Decision::RepairHull);
+}
+
+fn record_leave_shop(
+    _: Trigger<LeaveShop>,
+    level: CurrentRef<Level>,
+    round: Res<Round>,
+    mut replay: ResMut<Replay>,
+) {
+    let level = r!(level.get()).0;
+    replay.record(level, round.0, Decision::LeaveShop);
+}
+
+/// A replay being played back through the same systems as player input.
+///
+/// The player takes over when the replay runs out of decisions.
+#[derive(Resource, Debug)]
+pub struct Playback {
+    pub replay: Replay,
+    /// The index of the next decision to play.
+    cursor: usize,
+    /// The delay before the next decision, so the replay can be followed.
+    timer: Timer,
+}
+
+impl Configure for Playback {
+    fn configure(app: &mut App) {
+        app.add_systems(StateFlush, Screen::Gameplay.on_exit(stop_playback));
+        app.add_systems(
+            Update,
+            Screen::Gameplay.on_update(
+                (
+                    tick_playback_timer.in_set(UpdateSystems::TickTimers),
+                    play_replay.in_set(UpdateSystems::Update),
+                )
+                    .in_set(PausableSystems)
+                    .run_if(resource_exists::<Self>),
+            ),
+        );
+    }
+}
+
+impl Playback {
+    const DELAY: f32 = 0.4;
+
+    pub fn new(replay: Replay) -> Self {
+        Self {
+            replay,
+            cursor: 0,
+            timer: Timer::from_seconds(Self::DELAY, TimerMode::Once),
+        }
+    }
+}
+
+fn stop_playback(mut commands: Commands) {
+    commands.remove_resource::<Playback>();
+}
+
+fn tick_playback_timer(time: Res<Time>, mut playback: ResMut<Playback>) {
+    playback.timer.tick(time.delta());
+}
+
+fn play_replay(
+    mut commands: Commands,
+    level: CurrentRef<Level>,
+    phase: CurrentRef<Phase>,
+    menu: CurrentRef<Menu>,
+    round: Res<Round>,
+    mut playback: ResMut<Playback>,
+    mut player_deck: ResMut<PlayerDeck>,
+    mut helm_actions: ResMut<ActionState<HelmActions>>,
+) {
+    rq!(playback.timer.finished());
+    let level = r!(level.get()).0;
+    let Some(input) = playback.replay.inputs.get(playback.cursor).cloned() else {
+        // Hand the run over to the player, recording on from the end of the replay.
+        info!("Replay finished");
+        commands.insert_resource(playback.replay.clone());
+        commands.remove_resource::<Playback>();
+        return;
+    };
+
+    // Skip decisions that the run has already moved past.
+    if (input.level, input.round) < (level, round.0) {
+        warn!(
+            "Skipping replay decision from level {} round {}: {:?}",
+            input.level, input.round, input.decision,
+        );
+        playback.cursor += 1;
+        return;
+    }
+    rq!((input.level, input.round) == (level, round.0));
+
+    match input.decision {
+        Decision::Helm(action, hand_idx) => {
+            rq!(phase.is_in(&Phase::Helm) && menu.is_disabled());
+            player_deck.bypass_change_detection().hand_idx = hand_idx;
+            helm_actions.press(&action);
+        },
+        Decision::RemoveModule(idx) => {
+            rq!(phase.is_in(&Phase::Helm) && menu.is_disabled());
+            commands.trigger(RemoveReactorModule(idx));
+        },
+        Decision::InsertModule(hand_idx, slot_idx) => {
+            rq!(phase.is_in(&Phase::Helm) && menu.is_disabled());
+            commands.trigger(InsertReactorModule { hand_idx, slot_idx });
+        },
+        Decision::SwapModules(a, b) => {
+            rq!(phase.is_in(&Phase::Helm) && menu.is_disabled());
+            commands.trigger(SwapReactorModules(a, b));
+        },
+        Decision::SelectTarget(idx) => {
+            rq!(phase.is_in(&Phase::Helm) && menu.is_disabled());
+            commands.trigger(SelectTarget(idx));
+        },
+        Decision::PickUpgrades(picks) => {
+            rq!(menu.is_in(&Menu::Upgrade));
+            commands.trigger(PickUpgrades(picks));
+        },
+        Decision::BuyShopOffer(idx) => {
+            rq!(menu.is_in(&Menu::Shop));
+            commands.trigger(BuyShopOffer(idx));
+        },
+        Decision::ScrapModule(idx) => {
+            rq!(menu.is_in(&Menu::Shop));
+            commands.trigger(ScrapModule(idx));
+        },
+        Decision::RepairHull => {
+            rq!(menu.is_in(&Menu::Shop));
+            commands.trigger(RepairHull);
+        },
+        Decision::LeaveShop => {
+            rq!(menu.is_in(&Menu::Shop));
+            commands.trigger(LeaveShop);
+        },
+    }
+
+    playback.cursor += 1;
+    playback.timer.reset();
+}
@@ -0,0 +1,141 @@
+use crate::level::Level;
+use crate::phase::Phase;
+use crate::phase::Round;
+use crate::prelude::*;
+use crate::replay::Playback;
+use crate::save::Resume;
+
+pub(super) fn plugin(app: &mut App) {
+    app.configure::<GameRng>();
+}
+
+/// The source of all gameplay randomness, seeded once per run.
+///
+/// Each stream is derived from the run seed at level and round boundaries,
+/// so replaying a seed reproduces the same draws and upgrade offers no matter
+/// how much cosmetic randomness was consumed in between.
+#[derive(Resource, Debug)]
+pub struct GameRng {
+    /// The seed of the current run.
+    pub seed: u64,
+    /// A random id of the current run, distinct even between runs with the same seed.
+    pub run_id: u64,
+    /// Use this seed for new runs instead of a random one.
+    pub fixed_seed: Option<u64>,
+    /// Drawing modules from storage.
+    pub deck: StdRng,
+    /// Choosing weapons and spreading projectiles.
+    pub combat: StdRng,
+    /// Drawing modules for enemy reactors, kept apart from the player's draws so the
+    /// enemy intent previewed during the helm phase holds.
+    pub enemy: StdRng,
+    /// Generating upgrade offers.
+    pub upgrade: StdRng,
+    /// Visual and audio variety that does not affect gameplay.
+    pub cosmetic: StdRng,
+}
+
+impl Configure for GameRng {
+    fn configure(app: &mut App) {
+        app.init_resource::<Self>();
+        app.add_systems(
+            StateFlush,
+            (
+                Level::ANY.on_enter(reseed_for_level),
+                Phase::Helm.on_enter(reseed_for_round),
+            ),
+        );
+    }
+}
+
+impl Default for GameRng {
+    fn default() -> Self {
+        Self::new(thread_rng().r#gen())
+    }
+}
+
+impl GameRng {
+    const DECK: u64 = 1;
+    const COMBAT: u64 = 2;
+    const UPGRADE: u64 = 3;
+    const COSMETIC: u64 = 4;
+    const STAR: u64 = 5;
+    const ENEMY: u64 = 6;
+
+    pub fn new(seed: u64) -> Self {
+        let mut game_rng = Self {
+            seed,
+            run_id: 0,
+            fixed_seed: None,
+            deck: StdRng::seed_from_u64(0),
+            combat: StdRng::seed_from_u64(0),
+            enemy: StdRng::seed_from_u64(0),
+            upgrade: StdRng::seed_from_u64(0),
+            cosmetic: StdRng::seed_from_u64(0),
+        };
+        game_rng.reseed_level(0);
+        game_rng
+    }
+
+    /// Start a new run with the fixed seed, or a random seed if there is none.
+    pub fn new_run(&mut self) {
+        self.seed = self.fixed_seed.unwrap_or_else(|| thread_rng().r#gen());
+        self.run_id = thread_rng().r#gen();
+    }
+
+    /// Reseed all streams for the start of a level.
+    pub fn reseed_level(&mut self, level: usize) {
+        self.reseed_round(level, 0);
+        self.upgrade = self.stream(Self::UPGRADE, level, 0);
+        self.cosmetic = self.stream(Self::COSMETIC, level, 0);
+    }
+
+    /// Reseed the deck, combat and enemy streams for the start of a round.
+    pub fn reseed_round(&mut self, level: usize, round: usize) {
+        self.deck = self.stream(Self::DECK, level, round);
+        self.combat = self.stream(Self::COMBAT, level, round);
+        self.enemy = self.stream(Self::ENEMY, level, round);
+    }
+
+    /// The stream for generating a star past the authored levels in endless mode.
+    ///
+    /// Unlike the other streams, this is never stored, so the star is the same whenever
+    /// the level is entered with the same seed.
+    pub fn star(&self, level: usize) -> StdRng {
+        self.stream(Self::STAR, level, 0)
+    }
+
+    fn stream(&self, stream: u64, level: usize, round: usize) -> StdRng {
+        let seed = mix(mix(mix(self.seed ^ stream) ^ level as u64) ^ round as u64);
+        StdRng::seed_from_u64(seed)
+    }
+}
+
+/// The SplitMix64 finalizer, used to decorrelate nearby stream keys.
+fn mix(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^ (x >> 31)
+}
+
+fn reseed_for_level(
+    level: NextRef<Level>,
+    resume: Option<Res<Resume>>,
+    playback: Option<Res<Playback>>,
+    mut game_rng: ResMut<GameRng>,
+) {
+    let level = r!(level.get()).0;
+    if level == 0 && resume.is_none() {
+        match playback {
+            Some(playback) => game_rng.seed = playback.replay.seed,
+            None => game_rng.new_run(),
+        }
+    }
+    game_rng.reseed_level(level);
+}
+
+fn reseed_for_round(level: NextRef<Level>, round: Res<Round>, mut game_rng: ResMut<GameRng>) {
+    let level = r!(level.get()).0;
+    game_rng.reseed_round(level, round.0);
+}
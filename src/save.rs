@@ -0,0 +1,308 @@
+use crate::boss::EnemyBehavior;
+use crate::combat::death::Dead;
+use crate::combat::health::Health;
+use crate::combat::shield::Shield;
+use crate::combat::status::DamageOverTime;
+use crate::combat::status::StatusEffects;
+use crate::combat::target::EnemyIndex;
+use crate::combat::target::PlayerTarget;
+use crate::deck::EnemyDeck;
+use crate::deck::PlayerDeck;
+use crate::endless::EndlessMode;
+use crate::level::EnemyWave;
+use crate::level::Level;
+use crate::menu::Menu;
+use crate::menu::shop::Salvage;
+use crate::menu::shop::ShopStock;
+use crate::menu::shop::restock_shop;
+use crate::menu::upgrade::UpgradeHistory;
+use crate::phase::Phase;
+use crate::phase::Round;
+use crate::prelude::*;
+use crate::replay::Playback;
+use crate::rng::GameRng;
+use crate::screen::Screen;
+use crate::ship::EnemyShip;
+use crate::ship::PlayerShip;
+use crate::stats::Stats;
+use crate::util::storage;
+
+pub(super) fn plugin(app: &mut App) {
+    app.configure::<Resume>();
+
+    app.add_systems(
+        StateFlush,
+        (
+            Phase::Helm.on_enter(save_game),
+            Menu::Upgrade.on_enter(save_game),
+            Menu::Shop.on_enter(save_game.after(restock_shop)),
+            Menu::Victory.on_enter(delete_save),
+            Menu::Defeat.on_enter(delete_save),
+        )
+            .run_if(not(resource_exists::<Playback>)),
+    );
+}
+
+/// A snapshot of a campaign at the start of a helm phase or a level boundary menu.
+#[derive(Serialize, Deserialize, Default, Debug)]
+#[serde(default)]
+pub struct SaveGame {
+    pub version: u32,
+    pub seed: u64,
+    pub run_id: u64,
+    /// Whether the run continues past the authored levels.
+    pub endless: bool,
+    pub level: usize,
+    pub round: usize,
+    /// The player ship's health, or `None` if it's at max health.
+    pub player_health: Option<f32>,
+    /// The player ship's shield.
+    pub player_shield: f32,
+    pub player_damage_over_time: DamageOverTime,
+    pub player_statuses: StatusEffects,
+    pub player_deck: PlayerDeck,
+    /// The index of the current enemy wave.
+    pub wave: usize,
+    /// The enemy ships of the current wave by index, with `None` for defeated ships.
+    ///
+    /// Empty if the wave hasn't been engaged yet.
+    pub enemies: Vec<Option<EnemySave>>,
+    /// The index of the targeted enemy ship.
+    pub target: usize,
+    pub upgrade_history: UpgradeHistory,
+    pub salvage: Salvage,
+    pub stats: Stats,
+    /// The level boundary menu the campaign was saved in, or `None` if saved in a helm phase.
+    pub menu: Option<SaveMenu>,
+}
+
+impl SaveGame {
+    const FILE: &'static str = "save.ron";
+    /// Increment this when a change would break old save files.
+    const VERSION: u32 = 3;
+
+    /// Load the save file if it exists and is compatible with this version.
+    pub fn load() -> Option<Self> {
+        let save = ron::from_str::<Self>(&storage::read(Self::FILE)?).ok()?;
+        if save.version != Self::VERSION {
+            warn!(
+                "Ignoring save file with version {} (expected {})",
+                save.version,
+                Self::VERSION,
+            );
+            return None;
+        }
+
+        Some(save)
+    }
+
+    pub fn exists() -> bool {
+        Self::load().is_some()
+    }
+
+    fn store(&self) {
+        let text = r!(ron::ser::to_string_pretty(self, default()));
+        storage::write(Self::FILE, &text);
+    }
+
+    fn delete() {
+        storage::remove(Self::FILE);
+    }
+}
+
+/// A level boundary menu to reopen when resuming a campaign.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub enum SaveMenu {
+    Upgrade,
+    /// The shop, with its stock so resuming doesn't restock it.
+    Shop(ShopStock),
+}
+
+impl SaveMenu {
+    pub fn menu(&self) -> Menu {
+        match self {
+            Self::Upgrade => Menu::Upgrade,
+            Self::Shop(_) => Menu::Shop,
+        }
+    }
+}
+
+/// A snapshot of an enemy ship that hasn't been defeated.
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+#[serde(default)]
+pub struct EnemySave {
+    pub health: f32,
+    /// The ship's shield, or `None` if it has none.
+    pub shield: Option<f32>,
+    /// The ship's reactor deck, or `None` if the ship is scripted.
+    pub reactor: Option<PlayerDeck>,
+    /// The number of boss phases the ship has entered.
+    pub phase: usize,
+    pub damage_over_time: DamageOverTime,
+    pub statuses: StatusEffects,
+}
+
+/// The save game being resumed, until the first helm phase begins or the saved menu reopens.
+#[derive(Resource, Debug)]
+pub struct Resume(pub SaveGame);
+
+impl Configure for Resume {
+    fn configure(app: &mut App) {
+        app.add_systems(
+            StateFlush,
+            (
+                Screen::Gameplay.on_enter(restore_save.run_if(resource_exists::<Self>)),
+                Phase::Helm.on_enter(finish_resume.run_if(not(is_resuming_into_menu))),
+                Menu::Upgrade.on_enter(finish_resume),
+                Menu::Shop.on_enter(finish_resume.after(restock_shop)),
+            ),
+        );
+    }
+}
+
+fn restore_save(
+    resume: Res<Resume>,
+    mut game_rng: ResMut<GameRng>,
+    mut round: ResMut<Round>,
+    mut endless: ResMut<EndlessMode>,
+    mut player_deck: ResMut<PlayerDeck>,
+    mut upgrade_history: ResMut<UpgradeHistory>,
+    mut salvage: ResMut<Salvage>,
+    mut stats: ResMut<Stats>,
+    mut shop_stock: ResMut<ShopStock>,
+) {
+    let save = &resume.0;
+    game_rng.seed = save.seed;
+    game_rng.run_id = save.run_id;
+    round.0 = save.round;
+    endless.0 = save.endless;
+    *player_deck = save.player_deck.clone();
+    *upgrade_history = save.upgrade_history.clone();
+    *salvage = save.salvage.clone();
+    *stats = save.stats.clone();
+    if let Some(SaveMenu::Shop(stock)) = &save.menu {
+        *shop_stock = stock.clone();
+    }
+}
+
+fn is_resuming_into_menu(resume: Option<Res<Resume>>) -> bool {
+    resume.is_some_and(|x| x.0.menu.is_some())
+}
+
+fn finish_resume(mut commands: Commands) {
+    commands.remove_resource::<Resume>();
+}
+
+fn save_game(
+    level: NextRef<Level>,
+    menu: NextRef<Menu>,
+    round: Res<Round>,
+    game_rng: Res<GameRng>,
+    endless: Res<EndlessMode>,
+    player_deck: Res<PlayerDeck>,
+    enemy_wave: Res<EnemyWave>,
+    player_target: Res<PlayerTarget>,
+    upgrade_history: Res<UpgradeHistory>,
+    salvage: Res<Salvage>,
+    stats: Res<Stats>,
+    shop_stock: Res<ShopStock>,
+    resume: Option<Res<Resume>>,
+    player_query: Query<
+        (
+            &Health,
+            Option<&Shield>,
+            Option<&DamageOverTime>,
+            Option<&StatusEffects>,
+        ),
+        With<PlayerShip>,
+    >,
+    enemy_query: Query<
+        (
+            &EnemyIndex,
+            &EnemyDeck,
+            &EnemyBehavior,
+            &Health,
+            Option<&Shield>,
+            Option<&DamageOverTime>,
+            Option<&StatusEffects>,
+            Has<Dead>,
+        ),
+        With<EnemyShip>,
+    >,
+) {
+    let level = r!(level.get()).0;
+    let menu = if let Some(resume) = &resume {
+        resume.0.menu.clone()
+    } else {
+        match menu.get() {
+            Some(Menu::Upgrade) => Some(SaveMenu::Upgrade),
+            Some(Menu::Shop) => Some(SaveMenu::Shop(shop_stock.clone())),
+            _ => None,
+        }
+    };
+
+    // Ships are spawned fresh at the start of each level.
+    let (player_health, player_shield, player_damage_over_time, player_statuses, enemies) =
+        if let Some(resume) = &resume {
+            (
+                resume.0.player_health,
+                resume.0.player_shield,
+                resume.0.player_damage_over_time.clone(),
+                resume.0.player_statuses.clone(),
+                resume.0.enemies.clone(),
+            )
+        } else if round.0 == 0 {
+            (None, 0.0, default(), default(), vec![])
+        } else {
+            let (player_health, player_shield, player_dot, player_statuses) =
+                r!(player_query.single());
+            let mut enemies = vec![];
+            for (idx, deck, behavior, health, shield, dot, statuses, is_dead) in &enemy_query {
+                if enemies.len() <= idx.0 {
+                    enemies.resize(idx.0 + 1, None);
+                }
+                cq!(!is_dead);
+                enemies[idx.0] = Some(EnemySave {
+                    health: health.current,
+                    shield: shield.map(|x| x.current),
+                    reactor: deck.reactor.as_ref().map(|x| x.deck.clone()),
+                    phase: behavior.phase,
+                    damage_over_time: dot.cloned().unwrap_or_default(),
+                    statuses: statuses.cloned().unwrap_or_default(),
+                });
+            }
+            (
+                Some(player_health.current),
+                player_shield.map_or(0.0, |x| x.current),
+                player_dot.cloned().unwrap_or_default(),
+                player_statuses.cloned().unwrap_or_default(),
+                enemies,
+            )
+        };
+
+    SaveGame {
+        version: SaveGame::VERSION,
+        seed: game_rng.seed,
+        run_id: game_rng.run_id,
+        endless: endless.0,
+        level,
+        round: round.0,
+        player_health,
+        player_shield,
+        player_damage_over_time,
+        player_statuses,
+        player_deck: player_deck.clone(),
+        wave: enemy_wave.0,
+        enemies,
+        target: player_target.0,
+        upgrade_history: upgrade_history.clone(),
+        salvage: salvage.clone(),
+        stats: stats.clone(),
+        menu,
+    }
+    .store();
+}
+
+fn delete_save() {
+    SaveGame::delete();
+}
@@ -1,13 +1,14 @@
 use crate::level::Level;
 use crate::menu::Menu;
 use crate::prelude::*;
+use crate::save::Resume;
 use crate::screen::Screen;
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(
         StateFlush,
         (
-            Screen::Gameplay.on_edge(Level::disable, (Level(0).enter(), Level::trigger).chain()),
+            Screen::Gameplay.on_edge(Level::disable, (enter_first_level, Level::trigger).chain()),
             Menu::ANY.on_enter(
                 spawn_menu_overlay.run_if(
                     Screen::Gameplay
@@ -21,6 +22,10 @@ pub(super) fn plugin(app: &mut App) {
     app.configure::<(GameplayAssets, GameplayAction)>();
 }
 
+fn enter_first_level(resume: Option<Res<Resume>>, mut level: NextMut<Level>) {
+    level.enter(Level(resume.map_or(0, |resume| resume.0.level)));
+}
+
 fn spawn_menu_overlay(mut commands: Commands) {
     commands.spawn((
         widget::blocking_overlay(1),
@@ -96,27 +101,7 @@ pub struct GameplayAssets {
     #[asset(path = "image/module/full_glow.png")]
     pub module_slot_full_glow: Handle<Image>,
 
-    // Upgrade icons.
-    #[asset(path = "image/upgrade/upgrade_pack_nothing.png")]
-    pub upgrade_pack_nothing: Handle<Image>,
-    #[asset(path = "image/upgrade/upgrade_pack_repair.png")]
-    pub upgrade_pack_repair: Handle<Image>,
-    #[asset(path = "image/upgrade/upgrade_pack_missile.png")]
-    pub upgrade_pack_missile: Handle<Image>,
-    #[asset(path = "image/upgrade/upgrade_pack_laser.png")]
-    pub upgrade_pack_laser: Handle<Image>,
-    #[asset(path = "image/upgrade/upgrade_pack_fireball.png")]
-    pub upgrade_pack_fireball: Handle<Image>,
-    #[asset(path = "image/upgrade/upgrade_capacitor.png")]
-    pub upgrade_capacitor: Handle<Image>,
-    #[asset(path = "image/upgrade/upgrade_cooler.png")]
-    pub upgrade_cooler: Handle<Image>,
-    #[asset(path = "image/upgrade/upgrade_alloy.png")]
-    pub upgrade_alloy: Handle<Image>,
-
     // Spaceships.
-    #[asset(path = "image/ship/player.png")]
-    pub player_ship: Handle<Image>,
     #[asset(path = "image/ship/enemy.png")]
     pub enemy_ship: Handle<Image>,
 
@@ -153,6 +138,8 @@ pub enum GameplayAction {
     Pause,
     CloseMenu,
     ToggleHelp,
+    ToggleOverlay,
+    ToggleDeck,
 }
 
 impl Configure for GameplayAction {
@@ -164,7 +151,9 @@ impl Configure for GameplayAction {
                 .with(Self::Pause, KeyCode::Escape)
                 .with(Self::Pause, KeyCode::KeyP)
                 .with(Self::CloseMenu, KeyCode::KeyP)
-                .with(Self::ToggleHelp, KeyCode::KeyI),
+                .with(Self::ToggleHelp, KeyCode::KeyI)
+                .with(Self::ToggleOverlay, KeyCode::KeyO)
+                .with(Self::ToggleDeck, KeyCode::Tab),
         );
         app.add_plugins(InputManagerPlugin::<Self>::default());
         app.add_systems(
@@ -186,6 +175,15 @@ impl Configure for GameplayAction {
                         .will_update()
                         .and(action_just_pressed(Self::ToggleHelp)),
                 ),
+                Menu::Deck
+                    .enter()
+                    .in_set(UpdateSystems::RecordInput)
+                    .run_if(Menu::is_disabled.and(action_just_pressed(Self::ToggleDeck))),
+                Menu::pop.in_set(UpdateSystems::RecordInput).run_if(
+                    Menu::Deck
+                        .will_update()
+                        .and(action_just_pressed(Self::ToggleDeck)),
+                ),
             )),
         );
     }
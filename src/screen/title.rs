@@ -7,9 +7,12 @@ use crate::deck::DeckConfig;
 use crate::hud::HudConfig;
 use crate::level::LevelConfig;
 use crate::menu::Menu;
+use crate::menu::shop::ShopConfig;
+use crate::menu::upgrade::UpgradeConfig;
 use crate::phase::PhaseConfig;
 use crate::prelude::*;
 use crate::projectile::ProjectileConfig;
+use crate::relic::RelicConfig;
 use crate::screen::Screen;
 use crate::screen::gameplay::GameplayAssets;
 use crate::ship::ShipConfig;
@@ -35,7 +38,10 @@ pub(super) fn plugin(app: &mut App) {
             LevelConfig::progress.track_progress::<BevyState<Screen>>(),
             PhaseConfig::progress.track_progress::<BevyState<Screen>>(),
             ProjectileConfig::progress.track_progress::<BevyState<Screen>>(),
+            RelicConfig::progress.track_progress::<BevyState<Screen>>(),
+            ShopConfig::progress.track_progress::<BevyState<Screen>>(),
             ShipConfig::progress.track_progress::<BevyState<Screen>>(),
+            UpgradeConfig::progress.track_progress::<BevyState<Screen>>(),
         )),
     );
 
@@ -2,6 +2,8 @@ use crate::animation::oscillate::Oscillate;
 use crate::animation::shake::NodeShake;
 use crate::animation::shake::Shake;
 use crate::animation::shake::ShakeRotation;
+use crate::boss::Defeat;
+use crate::boss::EnemyBehavior;
 use crate::combat::damage::OnDamage;
 use crate::combat::death::OnDeath;
 use crate::combat::faction::Faction;
@@ -10,11 +12,11 @@ use crate::combat::health::HealthBarFill;
 use crate::combat::health::health_bar;
 use crate::core::camera::CameraRoot;
 use crate::core::physics::GameLayer;
+use crate::deck::DeckConfig;
 use crate::deck::PlayerDeck;
 use crate::hud::Hud;
 use crate::hud::HudConfig;
 use crate::hud::helm::hand::HandIndex;
-use crate::level::Level;
 use crate::prelude::*;
 use crate::screen::gameplay::GameplayAssets;
 use crate::util::math::ExponentialFit;
@@ -41,17 +43,18 @@ pub(super) fn plugin(app: &mut App) {
 
 pub fn player_ship(
     ship_config: &ShipConfig,
-    game_assets: &GameplayAssets,
-    health: f32,
+    deck_config: &DeckConfig,
+    player_deck: &PlayerDeck,
+    health: Health,
 ) -> impl Bundle {
-    let weapons = ship_config.player_weapons.clone();
-    let image = game_assets.player_ship.clone();
+    let weapons = player_deck.weapon_mounts.clone();
+    let image = deck_config.sprite(&player_deck.sprite);
 
     (
         Name::new("PlayerShip"),
         PlayerShip,
         Faction::Player,
-        Health::new(health),
+        health,
         Visibility::default(),
         RigidBody::Kinematic,
         MaxLinearSpeed(ship_config.player_speed_max),
@@ -71,7 +74,7 @@ pub fn player_ship(
                 PlayerShipBody,
                 Transform::default(),
                 Sprite::from_image(image),
-                Collider::rectangle(80.0, 10.0),
+                Collider::rectangle(player_deck.collider.x, player_deck.collider.y),
                 CollisionLayers::new(GameLayer::Player, LayerMask::ALL),
                 Children::spawn(SpawnWith(move |parent: &mut ChildSpawner| {
                     let rotation = Quat::turn_fraction(0.25);
@@ -129,7 +132,7 @@ fn shake_screen_on_damage(
 pub fn enemy_ship(
     ship_config: &ShipConfig,
     game_assets: &GameplayAssets,
-    health: f32,
+    health: Health,
 ) -> impl Bundle {
     let weapons = ship_config.enemy_weapons.clone();
     let health_bar_size = ship_config.enemy_health_bar_size;
@@ -139,7 +142,7 @@ pub fn enemy_ship(
         Name::new("EnemyShip"),
         EnemyShip,
         Faction::Enemy,
-        Health::new(health),
+        health,
         Sprite::from_image(game_assets.enemy_ship.clone()),
         RigidBody::Dynamic,
         Dominance(1),
@@ -172,12 +175,11 @@ pub fn enemy_ship(
 
 fn survive_on_one_health(
     trigger: Trigger<OnDeath>,
-    level: CurrentRef<Level>,
-    mut health_query: Query<&mut Health>,
+    mut health_query: Query<(&mut Health, &EnemyBehavior)>,
 ) {
     let target = r!(trigger.get_target());
-    let mut health = r!(health_query.get_mut(target));
-    rq!(!level.is_in(&Level(9)));
+    let (mut health, behavior) = r!(health_query.get_mut(target));
+    rq!(behavior.defeat == Defeat::Flee);
     health.current = 1.0;
 }
 
@@ -204,7 +206,6 @@ fn weapon() -> impl Bundle {
 #[derive(Asset, Reflect, Serialize, Deserialize, Default)]
 #[serde(deny_unknown_fields, default)]
 pub struct ShipConfig {
-    player_weapons: Vec<Vec2>,
     player_health_bar_offset: Vec2,
     player_health_bar_size: Vec2,
     player_accel_max: f32,
@@ -0,0 +1,615 @@
+//! Headless simulation of levels without rendering or physics.
+
+#![allow(dead_code)]
+
+use rand::seq::index::sample;
+
+use crate::boss::EnemyBehavior;
+use crate::combat::damage::mitigate_damage;
+use crate::combat::shield::Armor;
+use crate::combat::shield::Shield;
+use crate::combat::status::DamageOverTime;
+use crate::combat::status::StatusEffects;
+use crate::deck::EnemyDeck;
+use crate::deck::PlayerDeck;
+use crate::deck::policy::HelmPolicy;
+use crate::effect::ActionEffect;
+use crate::level::LevelConfig;
+use crate::level::LevelSetup;
+use crate::menu::shop::ShopConfig;
+use crate::menu::upgrade::UpgradeConfig;
+use crate::menu::upgrade::UpgradeHistory;
+use crate::menu::upgrade::generate_upgrades;
+use crate::modifier::LevelModifier;
+use crate::module::ModuleConfig;
+use crate::prelude::*;
+use crate::projectile::ProjectileConfig;
+use crate::rng::GameRng;
+
+/// Plays levels to completion, resolving damage analytically instead of through
+/// projectile collisions.
+pub struct Simulator<'a> {
+    pub level_config: &'a LevelConfig,
+    pub module_config: &'a ModuleConfig,
+    pub projectile_config: &'a ProjectileConfig,
+    pub upgrade_config: &'a UpgradeConfig,
+    pub shop_config: &'a ShopConfig,
+    /// Give up on a level after this many rounds.
+    pub max_rounds: usize,
+    /// End the helm phase after this many moves.
+    pub max_helm_moves: usize,
+}
+
+#[derive(Copy, Clone, Default, Eq, PartialEq, Debug)]
+pub enum Outcome {
+    #[default]
+    Victory,
+    Defeat,
+    Timeout,
+    /// The level uses rules the simulator can't model, so it wasn't played.
+    Unsupported,
+}
+
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct CampaignReport {
+    pub seed: u64,
+    pub outcome: Outcome,
+    pub levels: Vec<LevelReport>,
+    /// The keys of the upgrades picked after each level.
+    pub picks: Vec<Vec<String>>,
+}
+
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct LevelReport {
+    pub outcome: Outcome,
+    pub rounds: Vec<RoundReport>,
+}
+
+/// The state at the end of a round.
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct RoundReport {
+    pub round: usize,
+    pub player_health: f32,
+    pub enemy_health: f32,
+    pub flux: f32,
+    pub damage_given: f32,
+    pub damage_taken: f32,
+    pub player_actions: HashMap<String, usize>,
+    pub enemy_actions: HashMap<String, usize>,
+}
+
+impl Simulator<'_> {
+    /// Play every level in order until the player is defeated, picking random upgrades
+    /// between levels. The bot never spends salvage, so any carried hull damage is never repaired.
+    pub fn simulate_campaign(
+        &self,
+        mut player_deck: PlayerDeck,
+        policy: &mut dyn HelmPolicy,
+        seed: u64,
+    ) -> CampaignReport {
+        let mut game_rng = GameRng::new(seed);
+        let mut pick_rng = StdRng::seed_from_u64(seed);
+        let mut upgrade_history = UpgradeHistory::default();
+        let mut report = CampaignReport { seed, ..default() };
+
+        for (level, level_setup) in self.level_config.levels.iter().enumerate() {
+            let level_report =
+                self.simulate_level(level, level_setup, &mut player_deck, policy, &mut game_rng);
+            report.outcome = level_report.outcome;
+            report.levels.push(level_report);
+            if report.outcome != Outcome::Victory {
+                break;
+            }
+            if level_setup.victory || level + 1 == self.level_config.levels.len() {
+                break;
+            }
+
+            // Upgrade menu.
+            let mut upgrades = generate_upgrades(
+                &mut game_rng.upgrade,
+                self.upgrade_config,
+                &player_deck,
+                &upgrade_history,
+                level,
+            );
+            let pick_count = self.upgrade_config.pick_count.min(upgrades.len());
+            let mut picks = vec![];
+            for idx in sample(&mut pick_rng, upgrades.len(), pick_count) {
+                picks.push(upgrades[idx].key.clone());
+                upgrades[idx].apply(self.upgrade_config, &mut player_deck, &mut upgrade_history);
+            }
+            report.picks.push(picks);
+
+            // Shop.
+            if let Some(round) = report.levels.last().and_then(|x| x.rounds.last()) {
+                player_deck.hull_damage = self
+                    .shop_config
+                    .hull_damage(player_deck.max_health, round.player_health);
+            }
+
+            // Level exit.
+            player_deck.reset();
+        }
+
+        report
+    }
+
+    /// Play a level from its setup, starting with the player deck as it would be on level enter.
+    ///
+    /// Nebulae only slow projectiles down, so they don't change the outcome. Asteroids hit ships
+    /// at random, so levels with asteroid fields aren't played.
+    pub fn simulate_level(
+        &self,
+        level: usize,
+        level_setup: &LevelSetup,
+        player_deck: &mut PlayerDeck,
+        policy: &mut dyn HelmPolicy,
+        game_rng: &mut GameRng,
+    ) -> LevelReport {
+        let mut report = LevelReport::default();
+        if level_setup
+            .modifiers
+            .0
+            .iter()
+            .any(|x| matches!(x, LevelModifier::AsteroidField { .. }))
+        {
+            report.outcome = Outcome::Unsupported;
+            return report;
+        }
+
+        player_deck.extra_heat = level_setup.modifiers.extra_heat();
+        let mut wave = 0;
+        let mut enemies = SimEnemy::wave(level_setup, wave);
+        let mut player = SimShip {
+            health: (player_deck.max_health - player_deck.hull_damage).max(1.0),
+            max_health: player_deck.max_health,
+            ..default()
+        };
+        game_rng.reseed_level(level);
+
+        for round in 0..self.max_rounds {
+            let mut round_report = RoundReport { round, ..default() };
+
+            // Helm phase.
+            game_rng.reseed_round(level, round);
+            for _ in 0..self.max_helm_moves {
+                if !policy
+                    .decide(player_deck, self.module_config)
+                    .perform(player_deck)
+                {
+                    break;
+                }
+            }
+
+            // Reactor phase.
+            while player_deck.step_reactor(self.module_config) {}
+            round_report.flux = player_deck.flux;
+            round_report.damage_taken += player.damage(std::mem::take(&mut player_deck.meltdown));
+
+            // Player phase.
+            while let Some(action) = player_deck.step_player() {
+                let boost = player_deck
+                    .last_touched_idx
+                    .map_or(0.0, |idx| player_deck.reactor[idx].boost);
+                let flux = player_deck.flux + boost;
+                // The bot never switches targets, so it fires at the first living enemy.
+                let target = enemies
+                    .iter_mut()
+                    .find(|x| x.ship.is_alive())
+                    .map(|x| &mut x.ship);
+                round_report.damage_given += self.perform(
+                    &action,
+                    flux,
+                    SimDeck::Player(&mut *player_deck),
+                    &mut player,
+                    target,
+                    &mut game_rng.deck,
+                );
+                *round_report.player_actions.entry(action).or_default() += 1;
+            }
+
+            // Enemy phase. Defeated enemies flee or explode first, and the next wave arrives
+            // once they're gone. The new wave doesn't attack until the next round.
+            if !enemies.iter().any(|x| x.ship.is_alive()) {
+                wave += 1;
+                if wave >= level_setup.waves.len() {
+                    round_report.player_health = player.health;
+                    report.rounds.push(round_report);
+                    report.outcome = Outcome::Victory;
+                    return report;
+                }
+                enemies = SimEnemy::wave(level_setup, wave);
+            } else {
+                for enemy in &mut enemies {
+                    cq!(enemy.ship.is_alive());
+                    while enemy
+                        .behavior
+                        .update_phase(&mut enemy.deck, enemy.ship.health)
+                        .is_some()
+                    {}
+                    let meltdown = enemy
+                        .deck
+                        .play_turn(self.module_config, &mut game_rng.enemy);
+                    enemy.ship.damage(meltdown);
+                }
+                for enemy in &mut enemies {
+                    cq!(enemy.ship.is_alive());
+                    let enrage = enemy.behavior.enrage_flux(round);
+                    while let Some(action) = enemy.deck.step(round) {
+                        let flux = enemy.deck.flux + enemy.deck.boost() + enrage;
+                        round_report.damage_taken += self.perform(
+                            &action,
+                            flux,
+                            SimDeck::Enemy(&mut enemy.deck),
+                            &mut enemy.ship,
+                            Some(&mut player),
+                            &mut game_rng.enemy,
+                        );
+                        *round_report.enemy_actions.entry(action).or_default() += 1;
+                    }
+                }
+            }
+
+            // Solar flare.
+            let flare = level_setup.modifiers.solar_flare_damage();
+            if flare > 0.0 {
+                round_report.damage_taken += player.damage(flare);
+                for enemy in &mut enemies {
+                    cq!(enemy.ship.is_alive());
+                    round_report.damage_given += enemy.ship.damage(flare);
+                }
+            }
+
+            // Setup phase.
+            round_report.damage_taken += player.tick();
+            for enemy in &mut enemies {
+                round_report.damage_given += enemy.ship.tick();
+            }
+            player_deck.dissipate_heat(self.module_config);
+            while player_deck.step_setup(&mut game_rng.deck) {}
+
+            round_report.player_health = player.health;
+            round_report.enemy_health = enemies.iter().map(|x| x.ship.health).sum();
+            report.rounds.push(round_report);
+
+            // The player explodes at the start of the setup phase.
+            if !player.is_alive() {
+                report.outcome = Outcome::Defeat;
+                return report;
+            }
+        }
+
+        report.outcome = Outcome::Timeout;
+        report
+    }
+
+    /// Perform each effect of an action in order, like the effect handlers in the game,
+    /// returning the damage dealt to the target.
+    ///
+    /// Projectiles hit instantly, and damage over time is dealt in the setup phase.
+    fn perform(
+        &self,
+        action: &str,
+        flux: f32,
+        mut deck: SimDeck<'_>,
+        source: &mut SimShip,
+        mut target: Option<&mut SimShip>,
+        rng: &mut impl Rng,
+    ) -> f32 {
+        let action = rq!(self.module_config.actions.get(action));
+
+        let mut damage = 0.0;
+        for effect in &action.effects {
+            match effect {
+                ActionEffect::Projectile(key) => {
+                    let projectile = c!(self.projectile_config.projectiles.get(key));
+                    let target = cq!(target.as_deref_mut());
+                    damage += target.damage(projectile.damage * flux);
+                },
+                ActionEffect::Heal(x) if *x > f32::EPSILON => source.heal(x * flux),
+                ActionEffect::Shield(x) if *x > f32::EPSILON => source.shield.current += x * flux,
+                ActionEffect::Cool(x) => {
+                    let reactor = cq!(deck.reactor());
+                    for idx in 0..reactor.reactor.len() {
+                        reactor.cool_slot(idx, *x);
+                    }
+                },
+                ActionEffect::Draw(x) => {
+                    let reactor = cq!(deck.reactor());
+                    for _ in 0..*x {
+                        reactor.draw_random(rng);
+                    }
+                },
+                ActionEffect::AddFlux(x) => {
+                    let flux = deck.flux();
+                    *flux = (*flux + x).max(0.0);
+                },
+                ActionEffect::DamageOverTime { damage: x, rounds }
+                    if *x > f32::EPSILON && *rounds > 0 =>
+                {
+                    let target = cq!(target.as_deref_mut());
+                    target.damage_over_time.0.push((x * flux, *rounds));
+                },
+                ActionEffect::Status { name, rounds } if *rounds > 0 => {
+                    let target = cq!(target.as_deref_mut());
+                    target.statuses.apply(name.clone(), *rounds);
+                },
+                _ => {},
+            }
+        }
+
+        damage
+    }
+}
+
+/// The deck of the ship performing an action in a simulated level.
+enum SimDeck<'a> {
+    Player(&'a mut PlayerDeck),
+    Enemy(&'a mut EnemyDeck),
+}
+
+impl SimDeck<'_> {
+    fn flux(&mut self) -> &mut f32 {
+        match self {
+            Self::Player(deck) => &mut deck.flux,
+            Self::Enemy(deck) => &mut deck.flux,
+        }
+    }
+
+    /// The reactor to cool and draw modules into, if the ship has one.
+    fn reactor(&mut self) -> Option<&mut PlayerDeck> {
+        match self {
+            Self::Player(deck) => Some(&mut **deck),
+            Self::Enemy(deck) => deck.reactor.as_mut().map(|x| &mut x.deck),
+        }
+    }
+}
+
+/// The hull and defenses of a ship in a simulated level.
+#[derive(Default)]
+struct SimShip {
+    health: f32,
+    max_health: f32,
+    shield: Shield,
+    armor: Option<Armor>,
+    statuses: StatusEffects,
+    damage_over_time: DamageOverTime,
+}
+
+impl SimShip {
+    /// Take damage like the game does, returning the damage that got through to health.
+    fn damage(&mut self, damage: f32) -> f32 {
+        rq!(damage > 0.0);
+        let damage = mitigate_damage(
+            damage,
+            Some(&self.statuses),
+            Some(&mut self.shield),
+            self.armor.as_ref(),
+        );
+        self.health = (self.health - damage).max(0.0);
+        damage
+    }
+
+    fn heal(&mut self, amount: f32) {
+        self.health = (self.health + amount).min(self.max_health);
+    }
+
+    /// Deal damage over time, count down statuses and regenerate the shield at the start of
+    /// the setup phase, returning the damage that got through to health.
+    fn tick(&mut self) -> f32 {
+        let damage = if self.is_alive() {
+            let damage = self.damage_over_time.tick();
+            self.damage(damage)
+        } else {
+            0.0
+        };
+        self.statuses.tick();
+        self.shield.regenerate();
+
+        damage
+    }
+
+    fn is_alive(&self) -> bool {
+        self.health > f32::EPSILON
+    }
+}
+
+/// An enemy ship in a simulated level.
+struct SimEnemy {
+    deck: EnemyDeck,
+    behavior: EnemyBehavior,
+    ship: SimShip,
+}
+
+impl SimEnemy {
+    /// Spawn the enemy ships of a wave.
+    fn wave(level_setup: &LevelSetup, wave: usize) -> Vec<Self> {
+        level_setup
+            .waves
+            .get(wave)
+            .into_iter()
+            .flatten()
+            .map(|setup| {
+                let mut deck = setup.deck.clone();
+                if let Some(reactor) = &mut deck.reactor {
+                    reactor.deck.extra_heat = level_setup.modifiers.extra_heat();
+                }
+
+                Self {
+                    deck,
+                    behavior: setup.behavior.clone(),
+                    ship: SimShip {
+                        health: setup.deck.max_health,
+                        max_health: setup.deck.max_health,
+                        shield: setup.deck.shield.clone().unwrap_or_default(),
+                        armor: setup.deck.armor.clone(),
+                        ..default()
+                    },
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::deck::DeckConfig;
+    use crate::deck::policy::PolicyKind;
+    use crate::level::EnemySetup;
+
+    struct Configs {
+        deck: DeckConfig,
+        level: LevelConfig,
+        module: ModuleConfig,
+        projectile: ProjectileConfig,
+        upgrade: UpgradeConfig,
+        shop: ShopConfig,
+    }
+
+    impl Configs {
+        fn load() -> Self {
+            Self {
+                deck: ron::from_str(include_str!("../../assets/config/deck.ron")).unwrap(),
+                level: ron::from_str(include_str!("../../assets/config/level.ron")).unwrap(),
+                module: ron::from_str(include_str!("../../assets/config/module.ron")).unwrap(),
+                projectile: ron::from_str(include_str!("../../assets/config/projectile.ron"))
+                    .unwrap(),
+                upgrade: ron::from_str(include_str!("../../assets/config/upgrade.ron")).unwrap(),
+                shop: ron::from_str(include_str!("../../assets/config/shop.ron")).unwrap(),
+            }
+        }
+
+        fn simulator(&self, max_rounds: usize) -> Simulator<'_> {
+            Simulator {
+                level_config: &self.level,
+                module_config: &self.module,
+                projectile_config: &self.projectile,
+                upgrade_config: &self.upgrade,
+                shop_config: &self.shop,
+                max_rounds,
+                max_helm_moves: 100,
+            }
+        }
+
+        fn simulate(
+            &self,
+            level_setup: &LevelSetup,
+            mut player_deck: PlayerDeck,
+            max_rounds: usize,
+            seed: u64,
+        ) -> LevelReport {
+            self.simulator(max_rounds).simulate_level(
+                0,
+                level_setup,
+                &mut player_deck,
+                &mut *PolicyKind::GreedyMaxFlux.build(seed),
+                &mut GameRng::new(seed),
+            )
+        }
+    }
+
+    /// A level with a single enemy ship.
+    fn duel(deck: EnemyDeck) -> LevelSetup {
+        LevelSetup {
+            waves: vec![vec![EnemySetup { deck, ..default() }]],
+            ..default()
+        }
+    }
+
+    #[test]
+    fn test_same_seed_same_report() {
+        let configs = Configs::load();
+        let level_setup = &configs.level.levels[0];
+        let player_deck = &configs.deck.player_decks[0];
+        let report = configs.simulate(level_setup, player_deck.clone(), 50, 42);
+        assert!(!report.rounds.is_empty());
+        assert_eq!(
+            report,
+            configs.simulate(level_setup, player_deck.clone(), 50, 42),
+        );
+    }
+
+    #[test]
+    fn test_volley_through_shield_and_armor() {
+        let configs = Configs::load();
+        let mut player = SimShip {
+            health: 10.0,
+            max_health: 10.0,
+            ..default()
+        };
+        let mut enemy = SimShip {
+            health: 100.0,
+            max_health: 100.0,
+            shield: Shield::new(2.0, 0.0),
+            armor: Some(Armor {
+                flat: 1.0,
+                percent: 0.5,
+            }),
+            ..default()
+        };
+
+        // A laser at 4 flux deals 12 damage. The shield absorbs 2, then armor reduces
+        // the other 10 to (10 - 1) * 0.5.
+        let damage = configs.simulator(1).perform(
+            "laser",
+            4.0,
+            SimDeck::Player(&mut PlayerDeck::default()),
+            &mut player,
+            Some(&mut enemy),
+            &mut StdRng::seed_from_u64(0),
+        );
+        assert_eq!(damage, 4.5);
+        assert_eq!(enemy.shield.current, 0.0);
+        assert_eq!(enemy.health, 95.5);
+
+        // Vulnerable ships take 1.5x damage before the armor: (18 - 1) * 0.5.
+        enemy
+            .statuses
+            .apply(StatusEffects::VULNERABLE.to_string(), 1);
+        assert_eq!(enemy.damage(12.0), 8.5);
+    }
+
+    #[test]
+    fn test_victory() {
+        let configs = Configs::load();
+        let level_setup = duel(EnemyDeck {
+            max_health: 1.0,
+            ..default()
+        });
+        let player_deck = configs.deck.player_decks[0].clone();
+        let report = configs.simulate(&level_setup, player_deck, 50, 0);
+        assert_eq!(report.outcome, Outcome::Victory);
+        assert_eq!(report.rounds.last().unwrap().enemy_health, 0.0);
+    }
+
+    #[test]
+    fn test_defeat() {
+        let configs = Configs::load();
+        let level_setup = duel(EnemyDeck {
+            max_health: 1000.0,
+            start: vec!["laser".to_string()],
+            ..default()
+        });
+        let player_deck = PlayerDeck {
+            max_health: 1.0,
+            ..configs.deck.player_decks[0].clone()
+        };
+        let report = configs.simulate(&level_setup, player_deck, 50, 0);
+        assert_eq!(report.outcome, Outcome::Defeat);
+        assert_eq!(report.rounds.last().unwrap().player_health, 0.0);
+    }
+
+    #[test]
+    fn test_timeout() {
+        let configs = Configs::load();
+        let level_setup = duel(EnemyDeck {
+            max_health: 1_000_000.0,
+            ..default()
+        });
+        let player_deck = configs.deck.player_decks[0].clone();
+        let report = configs.simulate(&level_setup, player_deck, 3, 0);
+        assert_eq!(report.outcome, Outcome::Timeout);
+        assert_eq!(report.rounds.len(), 3);
+    }
+}
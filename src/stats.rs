@@ -1,13 +1,17 @@
 use crate::level::Level;
+use crate::menu::Menu;
 use crate::phase::Phase;
 use crate::prelude::*;
+use crate::save::Resume;
+use crate::screen::Screen;
 
 pub(super) fn plugin(app: &mut App) {
     app.configure::<Stats>();
 }
 
-#[derive(Resource, Reflect, Debug, Default, Clone)]
+#[derive(Resource, Reflect, Serialize, Deserialize, Debug, Default, Clone)]
 #[reflect(Resource)]
+#[serde(default)]
 pub struct Stats {
     pub actions: HashMap<String, usize>,
     pub highest_flux: f32,
@@ -15,6 +19,8 @@ pub struct Stats {
     pub damage_taken: f32,
     pub highest_damage: f32,
     pub total_rounds: usize,
+    /// Seconds spent playing, not including time paused or in menus.
+    pub duration: f32,
 }
 
 impl Configure for Stats {
@@ -24,10 +30,19 @@ impl Configure for Stats {
         app.add_systems(
             StateFlush,
             (
-                Level(0).on_enter(reset_stats),
+                Level(0).on_enter(reset_stats.run_if(not(resource_exists::<Resume>))),
                 Phase::Player.on_enter(increment_total_rounds),
             ),
         );
+        app.add_systems(
+            Update,
+            Screen::Gameplay.on_update(
+                tick_duration
+                    .in_set(UpdateSystems::TickTimers)
+                    .in_set(PausableSystems)
+                    .run_if(Menu::is_disabled),
+            ),
+        );
     }
 }
 
@@ -39,6 +54,10 @@ fn increment_total_rounds(mut stats: ResMut<Stats>) {
     stats.total_rounds += 1;
 }
 
+fn tick_duration(time: Res<Time>, mut stats: ResMut<Stats>) {
+    stats.duration += time.delta_secs();
+}
+
 pub fn stats_grid(stats: &Stats, level: usize) -> impl Bundle {
     let repairs = stats.actions.get("repair").copied().unwrap_or_default();
     let missiles = stats.actions.get("missile").copied().unwrap_or_default();
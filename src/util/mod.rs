@@ -9,6 +9,7 @@ pub mod math;
 pub mod patch;
 pub mod previous;
 pub mod selection;
+pub mod storage;
 
 #[allow(unused_imports)]
 pub mod prelude {
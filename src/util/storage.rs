@@ -0,0 +1,70 @@
+//! Persistent storage for small text files.
+//!
+//! Files are stored in the local config directory on native, and in local storage on web.
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::PathBuf;
+
+use crate::prelude::*;
+
+/// Read a stored file, or `None` if it doesn't exist.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn read(name: &str) -> Option<String> {
+    std::fs::read_to_string(path(name)?).ok()
+}
+
+/// Write a stored file, replacing its previous contents.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn write(name: &str, contents: &str) {
+    let path = r!(path(name));
+    r!(std::fs::write(path, contents));
+}
+
+/// Remove a stored file if it exists.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn remove(name: &str) {
+    let path = r!(path(name));
+    if path.exists() {
+        r!(std::fs::remove_file(path));
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn path(name: &str) -> Option<PathBuf> {
+    #[cfg(feature = "native")]
+    let path = {
+        let path = r!(dirs::config_local_dir()).join(env!("CARGO_PKG_NAME"));
+        // Create parent directories if necessary.
+        r!(std::fs::create_dir_all(&path));
+        path.join(name)
+    };
+    #[cfg(not(feature = "native"))]
+    let path = PathBuf::from(name);
+
+    Some(path)
+}
+
+/// Read a stored file, or `None` if it doesn't exist.
+#[cfg(target_arch = "wasm32")]
+pub fn read(name: &str) -> Option<String> {
+    local_storage()?.get_item(name).ok().flatten()
+}
+
+/// Write a stored file, replacing its previous contents.
+#[cfg(target_arch = "wasm32")]
+pub fn write(name: &str, contents: &str) {
+    let storage = r!(local_storage());
+    r!(storage.set_item(name, contents).ok());
+}
+
+/// Remove a stored file if it exists.
+#[cfg(target_arch = "wasm32")]
+pub fn remove(name: &str) {
+    let storage = r!(local_storage());
+    r!(storage.remove_item(name).ok());
+}
+
+#[cfg(target_arch = "wasm32")]
+fn local_storage() -> Option<web_sys::Storage> {
+    r!(web_sys::window()).local_storage().ok().flatten()
+}